@@ -0,0 +1,34 @@
+//! An opt-in view on an [`ExpandVec`] whose [`IndexMut`] implementation expands.
+use std::ops::{Index, IndexMut};
+
+use crate::{ExpandVec, Fill};
+
+/// A mutable view on an [`ExpandVec`] that expands when it is mutably indexed.
+///
+/// Created by [`ExpandVec::auto`]. Writing through `v.auto()[i]` behaves like
+/// [`ExpandVec::expand_get_mut`], while reading through it behaves like indexing the
+/// [`ExpandVec`] itself, and panics if the index is out of bounds.
+#[derive(Debug)]
+pub struct AutoExpand<'a, T, S> {
+    inner: &'a mut ExpandVec<T, S>,
+}
+
+impl<'a, T, S> AutoExpand<'a, T, S> {
+    pub(crate) fn new(inner: &'a mut ExpandVec<T, S>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T, S> Index<usize> for AutoExpand<'_, T, S> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.inner[index]
+    }
+}
+
+impl<T, S: Fill<T>> IndexMut<usize> for AutoExpand<'_, T, S> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.inner.expand_get_mut(index)
+    }
+}
@@ -0,0 +1,351 @@
+//! A compact, self-contained binary format for an [`ExpandVec`] of plain numeric items.
+//!
+//! Collections that consist mostly of long runs of zeros with islands of data are stored
+//! compactly, because runs of zeros (the default fill value) are run-length encoded.
+//!
+//! # Format
+//!
+//! All counts are unsigned LEB128 varints.
+//!
+//! * Header: the magic bytes `EXVC`, the format [`VERSION`] byte, a byte with the
+//!   [`BinaryElement::TAG`] of the item type, an endianness byte (`L` for little-endian or `B`
+//!   for big-endian items), and the number of items as a varint.
+//! * Records, until all items are covered: the length of a run of zeros as a varint, the number
+//!   of data items that follow it as a varint, and those data items, each encoded in the declared
+//!   endianness.
+//! * Trailer: the CRC-32 (IEEE) of all preceding bytes, as a little-endian `u32`.
+//!
+//! An item counts as a zero, and is encoded in a run, if all bytes of its encoding are zero. This
+//! means that a negative zero float is stored as data, and is read back exactly.
+//!
+//! [`ExpandVec::write_to`] always writes little-endian items; [`ExpandVec::read_from`] reads
+//! either.
+use std::collections::TryReserveError;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use crate::ExpandVec;
+
+/// The magic bytes at the start of the format.
+const MAGIC: [u8; 4] = *b"EXVC";
+
+/// The version of the format written by [`ExpandVec::write_to`].
+pub const VERSION: u8 = 1;
+
+const LITTLE_ENDIAN: u8 = b'L';
+const BIG_ENDIAN: u8 = b'B';
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// A plain numeric type that can be stored in the binary format.
+///
+/// This trait is sealed, and implemented for all fixed-size integer types and both float types.
+pub trait BinaryElement: Copy + Default + private::Sealed {
+    /// The byte that identifies the type in the header.
+    const TAG: u8;
+    /// The size of the encoding of a single item, in bytes.
+    const SIZE: usize;
+
+    /// Appends the little-endian encoding of `self` to `out`.
+    fn write_le(self, out: &mut Vec<u8>);
+
+    /// Returns `true` if all bytes of the encoding of `self` are zero.
+    fn is_zero(self) -> bool;
+
+    /// Decodes an item from exactly [`SIZE`](BinaryElement::SIZE) bytes.
+    fn read(bytes: &[u8], little_endian: bool) -> Self;
+}
+
+macro_rules! impl_binary_element {
+    ($($ty:ty => $tag:expr),* $(,)?) => {
+        $(
+            impl private::Sealed for $ty {}
+
+            impl BinaryElement for $ty {
+                const TAG: u8 = $tag;
+                const SIZE: usize = std::mem::size_of::<$ty>();
+
+                fn write_le(self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes())
+                }
+
+                fn is_zero(self) -> bool {
+                    self.to_le_bytes() == [0; std::mem::size_of::<$ty>()]
+                }
+
+                fn read(bytes: &[u8], little_endian: bool) -> Self {
+                    // The caller always passes exactly `SIZE` bytes.
+                    let bytes = bytes.try_into().unwrap();
+                    if little_endian {
+                        Self::from_le_bytes(bytes)
+                    } else {
+                        Self::from_be_bytes(bytes)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_binary_element! {
+    u8 => 1, u16 => 2, u32 => 3, u64 => 4, u128 => 5,
+    i8 => 6, i16 => 7, i32 => 8, i64 => 9, i128 => 10,
+    f32 => 11, f64 => 12,
+}
+
+/// The error returned when reading the binary format fails.
+#[derive(Debug)]
+pub enum DecodeError {
+    /// Reading from the underlying reader failed.
+    Io(io::Error),
+    /// The input ended before the format was complete.
+    Truncated,
+    /// The input does not start with the magic bytes.
+    BadMagic,
+    /// The format version is not supported.
+    UnsupportedVersion(u8),
+    /// The input stores a different item type than the one that was requested.
+    TypeMismatch {
+        /// The [`BinaryElement::TAG`] of the requested type.
+        expected: u8,
+        /// The tag found in the input.
+        found: u8,
+    },
+    /// The endianness byte is neither `L` nor `B`.
+    BadEndianness(u8),
+    /// The checksum in the trailer does not match the contents.
+    ChecksumMismatch {
+        /// The checksum stored in the trailer.
+        stored: u32,
+        /// The checksum computed over the contents.
+        computed: u32,
+    },
+    /// The contents are inconsistent, even though the checksum matches.
+    Corrupt(&'static str),
+    /// The memory for the items could not be allocated.
+    AllocationFailure(TryReserveError),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read input: {err}"),
+            Self::Truncated => write!(f, "input is truncated"),
+            Self::BadMagic => write!(f, "input does not start with the expected magic bytes"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {version}")
+            }
+            Self::TypeMismatch { expected, found } => write!(
+                f,
+                "input stores items with type tag {found}, but type tag {expected} was expected"
+            ),
+            Self::BadEndianness(byte) => write!(f, "invalid endianness byte {byte:#04x}"),
+            Self::ChecksumMismatch { stored, computed } => write!(
+                f,
+                "checksum mismatch: stored {stored:#010x}, computed {computed:#010x}"
+            ),
+            Self::Corrupt(reason) => write!(f, "input is corrupt: {reason}"),
+            Self::AllocationFailure(_) => write!(f, "memory allocation failed while decoding"),
+        }
+    }
+}
+
+impl Error for DecodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::AllocationFailure(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for DecodeError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<TryReserveError> for DecodeError {
+    fn from(err: TryReserveError) -> Self {
+        Self::AllocationFailure(err)
+    }
+}
+
+/// The lookup table for the CRC-32 (IEEE) checksum.
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                crc >> 1 ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, &byte| {
+        CRC_TABLE[((crc ^ u32::from(byte)) & 0xff) as usize] ^ crc >> 8
+    })
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8)
+}
+
+/// A cursor over the contents of the input, which precede the checksum.
+struct Cursor<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        if n > self.bytes.len() {
+            return Err(DecodeError::Truncated);
+        }
+        let (taken, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64, DecodeError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            let bits = u64::from(byte & 0x7f);
+            if bits << shift >> shift != bits {
+                return Err(DecodeError::Corrupt("varint overflows 64 bits"));
+            }
+            value |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(DecodeError::Corrupt("varint overflows 64 bits"))
+    }
+
+    /// Reads a varint that counts items, which must fit in a `usize`.
+    fn count(&mut self) -> Result<usize, DecodeError> {
+        usize::try_from(self.varint()?).map_err(|_| DecodeError::Corrupt("count overflows usize"))
+    }
+}
+
+impl<T: BinaryElement> ExpandVec<T> {
+    /// Writes the items in the compact binary format described in the [`binary`](crate::binary)
+    /// module, run-length encoding runs of zeros.
+    ///
+    /// Only the items are written. Settings such as the [`max_len`](ExpandVec::max_len) are not.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&MAGIC);
+        out.extend_from_slice(&[VERSION, T::TAG, LITTLE_ENDIAN]);
+        write_varint(&mut out, self.inner.len() as u64);
+        let mut rest = &self.inner[..];
+        while !rest.is_empty() {
+            let zeros = rest
+                .iter()
+                .position(|item| !item.is_zero())
+                .unwrap_or(rest.len());
+            rest = &rest[zeros..];
+            let data = rest
+                .iter()
+                .position(|item| item.is_zero())
+                .unwrap_or(rest.len());
+            write_varint(&mut out, zeros as u64);
+            write_varint(&mut out, data as u64);
+            for &item in &rest[..data] {
+                item.write_le(&mut out)
+            }
+            rest = &rest[data..];
+        }
+        let checksum = crc32(&out);
+        out.extend_from_slice(&checksum.to_le_bytes());
+        writer.write_all(&out)
+    }
+
+    /// Reads a collection in the compact binary format described in the
+    /// [`binary`](crate::binary) module.
+    ///
+    /// The whole input is read and its checksum is verified before any items are decoded.
+    /// Corrupt or truncated input, or input that stores a different item type, results in a
+    /// [`DecodeError`] rather than a panic.
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, DecodeError> {
+        let mut input = Vec::new();
+        reader.read_to_end(&mut input)?;
+        let Some(contents_len) = input.len().checked_sub(4) else {
+            return Err(DecodeError::Truncated);
+        };
+        let (contents, trailer) = input.split_at(contents_len);
+        if contents.len() >= MAGIC.len() && contents[..MAGIC.len()] != MAGIC {
+            return Err(DecodeError::BadMagic);
+        }
+        let stored = u32::from_le_bytes(trailer.try_into().unwrap());
+        let computed = crc32(contents);
+        if stored != computed {
+            return Err(DecodeError::ChecksumMismatch { stored, computed });
+        }
+
+        let mut cursor = Cursor { bytes: contents };
+        cursor.take(MAGIC.len())?;
+        let version = cursor.byte()?;
+        if version != VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+        let tag = cursor.byte()?;
+        if tag != T::TAG {
+            return Err(DecodeError::TypeMismatch {
+                expected: T::TAG,
+                found: tag,
+            });
+        }
+        let little_endian = match cursor.byte()? {
+            LITTLE_ENDIAN => true,
+            BIG_ENDIAN => false,
+            byte => return Err(DecodeError::BadEndianness(byte)),
+        };
+        let len = cursor.count()?;
+
+        let mut inner = Vec::new();
+        while inner.len() < len {
+            let zeros = cursor.count()?;
+            let data = cursor.count()?;
+            let run = zeros
+                .checked_add(data)
+                .filter(|&run| run > 0 && run <= len - inner.len())
+                .ok_or(DecodeError::Corrupt("record does not fit the length"))?;
+            let bytes = cursor.take(data.checked_mul(T::SIZE).ok_or(DecodeError::Truncated)?)?;
+            inner.try_reserve(run)?;
+            inner.resize(inner.len() + zeros, T::default());
+            inner.extend(
+                bytes
+                    .chunks_exact(T::SIZE)
+                    .map(|item| T::read(item, little_endian)),
+            );
+        }
+        if !cursor.bytes.is_empty() {
+            return Err(DecodeError::Corrupt("trailing bytes after the last record"));
+        }
+        Ok(Self::from(inner))
+    }
+}
@@ -0,0 +1,161 @@
+//! A collection that expands like an [`ExpandVec`](crate::ExpandVec), but stores its items in
+//! fixed-size pages that are allocated on demand.
+use crate::{DefaultFill, ExpandError, Fill, FillValue};
+
+/// The number of items per page used by [`ExpandChunks::new`].
+pub const DEFAULT_PAGE_SIZE: usize = 4096;
+
+/// A collection that expands to provide a mutable reference to items beyond the stored
+/// collection, storing its items in fixed-size pages.
+///
+/// Growth never moves items that are already stored: a write beyond the end only allocates the
+/// page it lands in. Pages between the previous end and that page are not allocated until they
+/// are written to, and read as the fill value in the meantime.
+#[derive(Debug, Clone)]
+pub struct ExpandChunks<T, S = DefaultFill> {
+    pages: Vec<Option<Box<[T]>>>,
+    page_size: usize,
+    len: usize,
+    /// The value that is read for every slot in a page that has not been allocated.
+    gap: T,
+    fill: S,
+}
+
+impl<T: Default> ExpandChunks<T> {
+    /// Creates an empty collection with pages of [`DEFAULT_PAGE_SIZE`] items.
+    pub fn new() -> Self {
+        Self::with_page_size(DEFAULT_PAGE_SIZE)
+    }
+
+    /// Creates an empty collection with pages of `page_size` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is zero.
+    pub fn with_page_size(page_size: usize) -> Self {
+        Self::with_filler(page_size, DefaultFill)
+    }
+}
+
+impl<T: Clone> ExpandChunks<T, FillValue<T>> {
+    /// Creates an empty collection with pages of `page_size` items, that reads and fills
+    /// unwritten slots with clones of `value`, rather than with [`Default::default`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is zero.
+    pub fn with_fill(page_size: usize, value: T) -> Self {
+        Self::with_filler(page_size, FillValue(value))
+    }
+}
+
+impl<T, S: Fill<T>> ExpandChunks<T, S> {
+    fn with_filler(page_size: usize, fill: S) -> Self {
+        assert!(page_size > 0, "page size must be greater than zero");
+        Self {
+            pages: Vec::new(),
+            page_size,
+            len: 0,
+            gap: fill.fill(),
+            fill,
+        }
+    }
+
+    /// Returns the page that holds `index`, allocating it if necessary.
+    fn page_mut(&mut self, index: usize) -> &mut [T] {
+        let page = index / self.page_size;
+        if page >= self.pages.len() {
+            let pages = page
+                .checked_add(1)
+                .unwrap_or_else(|| panic!("{}", ExpandError::IndexOverflow));
+            self.pages.resize_with(pages, || None);
+        }
+        let (page_size, fill) = (self.page_size, &self.fill);
+        self.pages[page].get_or_insert_with(|| (0..page_size).map(|_| fill.fill()).collect())
+    }
+
+    /// Appends an element to the back of a collection.
+    pub fn push(&mut self, value: T) {
+        *self.expand_get_mut(self.len) = value;
+    }
+
+    /// Returns a mutable reference to the element at `index`, or `None` if it is out of bounds.
+    ///
+    /// If the page holding the element has not been allocated yet, it is allocated first.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len {
+            return None;
+        }
+        let offset = index % self.page_size;
+        Some(&mut self.page_mut(index)[offset])
+    }
+
+    /// Always returns a mutable reference to an element.
+    /// If the index points beyond the length of the collection, the length is expanded to fit the
+    /// index. Only the page holding the index is allocated; the pages before it are not.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is `usize::MAX`.
+    pub fn expand_get_mut(&mut self, index: usize) -> &mut T {
+        if index >= self.len {
+            self.len = index
+                .checked_add(1)
+                .unwrap_or_else(|| panic!("{}", ExpandError::IndexOverflow));
+        }
+        let offset = index % self.page_size;
+        &mut self.page_mut(index)[offset]
+    }
+}
+
+impl<T, S> ExpandChunks<T, S> {
+    /// Returns the filler that creates new items when the collection expands.
+    pub fn filler(&self) -> &S {
+        &self.fill
+    }
+
+    /// Returns the number of items per page.
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// Returns the number of elements, including the ones in unallocated pages.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the collection contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of pages that have been allocated.
+    pub fn allocated_pages(&self) -> usize {
+        self.pages.iter().filter(|page| page.is_some()).count()
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if it is out of bounds.
+    ///
+    /// Slots in pages that have not been allocated read as the fill value.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        match self.pages.get(index / self.page_size) {
+            Some(Some(page)) => Some(&page[index % self.page_size]),
+            _ => Some(&self.gap),
+        }
+    }
+
+    /// Returns an iterator over all elements in index order, including the ones in unallocated
+    /// pages.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        (0..self.len).map(|index| self.get(index).unwrap())
+    }
+}
+
+impl<T, S: Fill<T> + Default> Default for ExpandChunks<T, S> {
+    fn default() -> Self {
+        Self::with_filler(DEFAULT_PAGE_SIZE, S::default())
+    }
+}
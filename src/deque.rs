@@ -0,0 +1,164 @@
+//! A double-ended collection that expands at either end to provide a mutable reference to items
+//! at any signed index.
+use std::collections::VecDeque;
+
+use crate::{DefaultFill, ExpandError, Fill, FillValue};
+
+/// A double-ended collection that expands at the front or the back to provide a mutable reference
+/// to items beyond the stored collection.
+///
+/// Items are addressed by [`isize`] indices, which may be negative. The stored items cover the
+/// indices from [`min_index`](ExpandDeque::min_index) up to and including
+/// [`max_index`](ExpandDeque::max_index). The inner collection is a [`VecDeque`], so growing at
+/// either end is amortized in the number of new items.
+#[derive(Debug, Clone)]
+pub struct ExpandDeque<T, S = DefaultFill> {
+    inner: VecDeque<T>,
+    /// The index of the front item.
+    offset: isize,
+    fill: S,
+}
+
+impl<T> ExpandDeque<T> {
+    pub fn new() -> Self {
+        Self::with_filler(DefaultFill)
+    }
+}
+
+impl<T: Clone> ExpandDeque<T, FillValue<T>> {
+    /// Creates an empty collection that fills any gaps created by expansion with clones of
+    /// `value`, rather than with [`Default::default`].
+    pub fn with_fill(value: T) -> Self {
+        Self::with_filler(FillValue(value))
+    }
+}
+
+impl<T, S> ExpandDeque<T, S> {
+    fn with_filler(fill: S) -> Self {
+        Self {
+            inner: VecDeque::new(),
+            offset: 0,
+            fill,
+        }
+    }
+
+    /// Returns the filler that creates new items when the collection expands.
+    pub fn filler(&self) -> &S {
+        &self.fill
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the collection contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the index of the front item, or `None` if the collection is empty.
+    pub fn min_index(&self) -> Option<isize> {
+        (!self.is_empty()).then_some(self.offset)
+    }
+
+    /// Returns the index of the back item, or `None` if the collection is empty.
+    pub fn max_index(&self) -> Option<isize> {
+        (!self.is_empty()).then(|| index_at(self.offset, self.inner.len() - 1))
+    }
+
+    /// Returns the position of `index` in the inner collection, if it is in bounds.
+    fn position(&self, index: isize) -> Option<usize> {
+        let position = usize::try_from(index.checked_sub(self.offset)?).ok()?;
+        (position < self.inner.len()).then_some(position)
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if it is out of bounds.
+    pub fn get(&self, index: isize) -> Option<&T> {
+        self.inner.get(self.position(index)?)
+    }
+
+    /// Returns a mutable reference to the element at `index`, or `None` if it is out of bounds.
+    pub fn get_mut(&mut self, index: isize) -> Option<&mut T> {
+        let position = self.position(index)?;
+        self.inner.get_mut(position)
+    }
+
+    /// Returns an iterator over the elements and their indices, from front to back.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (isize, &T)> + ExactSizeIterator + '_ {
+        let offset = self.offset;
+        self.inner
+            .iter()
+            .enumerate()
+            .map(move |(position, item)| (index_at(offset, position), item))
+    }
+
+    /// Returns a mutable iterator over the elements and their indices, from front to back.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (isize, &mut T)> + ExactSizeIterator + '_ {
+        let offset = self.offset;
+        self.inner
+            .iter_mut()
+            .enumerate()
+            .map(move |(position, item)| (index_at(offset, position), item))
+    }
+
+    /// Returns the inner [`VecDeque`] together with the index of its front item.
+    pub fn raw_parts(self) -> (isize, VecDeque<T>) {
+        (self.offset, self.inner)
+    }
+}
+
+impl<T, S: Fill<T>> ExpandDeque<T, S> {
+    /// Always returns a mutable reference to an element.
+    /// If the index points before the front or beyond the back of the inner collection, it is
+    /// expanded at that end with fill values to fit the index. In that case, a mutable reference
+    /// to this new outermost item under the index is returned.
+    ///
+    /// The first item added to an empty collection fixes its position at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the collection would span more than `usize::MAX` indices, such as both
+    /// `isize::MIN` and `isize::MAX`.
+    pub fn expand_get_mut(&mut self, index: isize) -> &mut T {
+        let overflow = || -> usize { panic!("{}", ExpandError::IndexOverflow) };
+        if self.inner.is_empty() {
+            self.offset = index;
+            self.inner.push_back(self.fill.fill());
+        } else if index < self.offset {
+            let remaining = index.abs_diff(self.offset);
+            // Check the new length before the inner collection is touched.
+            remaining
+                .checked_add(self.inner.len())
+                .unwrap_or_else(overflow);
+            self.inner.reserve(remaining);
+            for _ in 0..remaining {
+                self.inner.push_front(self.fill.fill());
+            }
+            self.offset = index;
+        } else {
+            let position = index.abs_diff(self.offset);
+            if position >= self.inner.len() {
+                let len = position.checked_add(1).unwrap_or_else(overflow);
+                self.inner.resize_with(len, || self.fill.fill());
+            }
+        }
+        // We can safely unwrap since the inner collection was extended to cover the index.
+        self.get_mut(index).unwrap()
+    }
+}
+
+/// Returns the index of the item at `position` in the inner collection, whose front item is at
+/// `offset`.
+fn index_at(offset: isize, position: usize) -> isize {
+    // Every stored item has an `isize` index, so this never actually wraps.
+    offset.wrapping_add_unsigned(position)
+}
+
+impl<T, S: Default> Default for ExpandDeque<T, S> {
+    fn default() -> Self {
+        Self::with_filler(S::default())
+    }
+}
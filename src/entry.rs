@@ -0,0 +1,146 @@
+//! An entry API for [`ExpandVec`], modelled on the one of
+//! [`HashMap`](std::collections::HashMap).
+use std::mem;
+
+use crate::{ExpandVec, Fill};
+
+/// A view into a single slot of an [`ExpandVec`], which may be occupied or vacant.
+///
+/// Created by [`ExpandVec::entry`].
+#[derive(Debug)]
+pub enum Entry<'a, T, S> {
+    /// A slot that is stored and was explicitly written.
+    Occupied(OccupiedEntry<'a, T, S>),
+    /// A slot that is out of bounds, or a hole.
+    Vacant(VacantEntry<'a, T, S>),
+}
+
+/// A view into an occupied slot of an [`ExpandVec`].
+#[derive(Debug)]
+pub struct OccupiedEntry<'a, T, S> {
+    vec: &'a mut ExpandVec<T, S>,
+    index: usize,
+}
+
+/// A view into a vacant slot of an [`ExpandVec`].
+///
+/// The slot is either out of bounds, or a hole if occupancy is tracked.
+#[derive(Debug)]
+pub struct VacantEntry<'a, T, S> {
+    vec: &'a mut ExpandVec<T, S>,
+    index: usize,
+}
+
+impl<T, S> Entry<'_, T, S> {
+    /// Returns the index of the slot.
+    pub fn key(&self) -> usize {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Calls `f` with the value in the slot if it is occupied, and returns the entry.
+    pub fn and_modify<F: FnOnce(&mut T)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, T, S: Fill<T>> Entry<'a, T, S> {
+    /// Ensures the slot is occupied by inserting `default` if it is vacant, and returns a
+    /// mutable reference to the value in the slot.
+    pub fn or_insert(self, default: T) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures the slot is occupied by inserting the result of `default` if it is vacant, and
+    /// returns a mutable reference to the value in the slot.
+    pub fn or_insert_with<F: FnOnce() -> T>(self, default: F) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures the slot is occupied by inserting the result of calling `default` with the index
+    /// if it is vacant, and returns a mutable reference to the value in the slot.
+    pub fn or_insert_with_key<F: FnOnce(usize) -> T>(self, default: F) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+}
+
+impl<'a, T: Default, S: Fill<T>> Entry<'a, T, S> {
+    /// Ensures the slot is occupied by inserting [`Default::default`] if it is vacant, and
+    /// returns a mutable reference to the value in the slot.
+    pub fn or_default(self) -> &'a mut T {
+        self.or_insert_with(T::default)
+    }
+}
+
+impl<'a, T, S> OccupiedEntry<'a, T, S> {
+    pub(crate) fn new(vec: &'a mut ExpandVec<T, S>, index: usize) -> Self {
+        Self { vec, index }
+    }
+
+    /// Returns the index of the slot.
+    pub fn key(&self) -> usize {
+        self.index
+    }
+
+    /// Returns a reference to the value in the slot.
+    pub fn get(&self) -> &T {
+        &self.vec.inner[self.index]
+    }
+
+    /// Returns a mutable reference to the value in the slot.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.vec.inner[self.index]
+    }
+
+    /// Converts the entry into a mutable reference to the value in the slot, with the lifetime
+    /// of the collection.
+    pub fn into_mut(self) -> &'a mut T {
+        &mut self.vec.inner[self.index]
+    }
+
+    /// Replaces the value in the slot with `value`, and returns the old value.
+    pub fn insert(&mut self, value: T) -> T {
+        mem::replace(self.get_mut(), value)
+    }
+}
+
+impl<'a, T, S> VacantEntry<'a, T, S> {
+    pub(crate) fn new(vec: &'a mut ExpandVec<T, S>, index: usize) -> Self {
+        Self { vec, index }
+    }
+
+    /// Returns the index of the slot.
+    pub fn key(&self) -> usize {
+        self.index
+    }
+}
+
+impl<'a, T, S: Fill<T>> VacantEntry<'a, T, S> {
+    /// Stores `value` in the slot, expanding the collection with fill values to fit it if it is
+    /// out of bounds, and returns a mutable reference to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the expansion would exceed the [`max_len`](ExpandVec::max_len).
+    pub fn insert(self, value: T) -> &'a mut T {
+        self.vec.store(self.index, value);
+        &mut self.vec.inner[self.index]
+    }
+}
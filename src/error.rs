@@ -0,0 +1,48 @@
+//! The error returned by fallible expansion.
+use std::collections::TryReserveError;
+use std::error::Error;
+use std::fmt;
+
+/// The error returned when a collection cannot be expanded to fit an index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpandError {
+    /// Fitting the index would make the collection longer than its configured maximum length.
+    OverLimit {
+        /// The length that would have been required to fit the index.
+        len: usize,
+        /// The configured maximum length.
+        max_len: usize,
+    },
+    /// The length required to fit the index does not fit in a `usize`.
+    IndexOverflow,
+    /// The allocator could not provide the memory to fit the index.
+    AllocationFailure(TryReserveError),
+}
+
+impl fmt::Display for ExpandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OverLimit { len, max_len } => write!(
+                f,
+                "expanding to a length of {len} exceeds the maximum length of {max_len}"
+            ),
+            Self::IndexOverflow => write!(f, "expanding to fit the index overflows a usize"),
+            Self::AllocationFailure(_) => write!(f, "memory allocation failed while expanding"),
+        }
+    }
+}
+
+impl Error for ExpandError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::AllocationFailure(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<TryReserveError> for ExpandError {
+    fn from(err: TryReserveError) -> Self {
+        Self::AllocationFailure(err)
+    }
+}
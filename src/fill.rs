@@ -0,0 +1,44 @@
+//! Strategies for filling the slots that are created when an [`ExpandVec`](crate::ExpandVec)
+//! expands.
+
+/// Creates the values that fill new slots when a collection expands.
+pub trait Fill<T> {
+    /// Returns a new value for a slot created by expansion.
+    fn fill(&self) -> T;
+
+    /// Returns a reference to the fill value, if the filler holds one.
+    ///
+    /// This allows [`ExpandVec::get_or_default`](crate::ExpandVec::get_or_default) to borrow the
+    /// fill value rather than create and cache one. The default implementation returns `None`.
+    fn fill_ref(&self) -> Option<&T> {
+        None
+    }
+}
+
+/// Fills new slots with [`Default::default`].
+///
+/// This is the filler used by [`ExpandVec::new`](crate::ExpandVec::new).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DefaultFill;
+
+impl<T: Default> Fill<T> for DefaultFill {
+    fn fill(&self) -> T {
+        T::default()
+    }
+}
+
+/// Fills new slots with clones of a stored value.
+///
+/// This is the filler used by [`ExpandVec::with_fill`](crate::ExpandVec::with_fill).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FillValue<T>(pub T);
+
+impl<T: Clone> Fill<T> for FillValue<T> {
+    fn fill(&self) -> T {
+        self.0.clone()
+    }
+
+    fn fill_ref(&self) -> Option<&T> {
+        Some(&self.0)
+    }
+}
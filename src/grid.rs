@@ -0,0 +1,224 @@
+//! A two-dimensional grid that expands to provide a mutable reference to cells beyond the stored
+//! rows and columns.
+use std::mem;
+
+use crate::{DefaultFill, ExpandError, Fill, FillValue};
+
+/// The way in which an [`ExpandGrid`] stores its cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// All rows have the same length and are stored back to back in a single buffer.
+    ///
+    /// Widening the grid moves every row into a new buffer, but reads and writes are a single
+    /// index computation away.
+    Dense,
+    /// Every row is stored separately and only holds as many cells as have been expanded into it.
+    ///
+    /// Widening one row leaves the other rows untouched.
+    Ragged,
+}
+
+#[derive(Debug, Clone)]
+enum Storage<T> {
+    Dense(Vec<T>),
+    Ragged(Vec<Vec<T>>),
+}
+
+/// A two-dimensional grid that expands to provide a mutable reference to cells beyond the stored
+/// rows and columns.
+///
+/// Cells are addressed by `(row, col)`. The [`ExpandGrid::expand_get_mut`] method behaves like
+/// [`ExpandVec::expand_get_mut`](crate::ExpandVec::expand_get_mut) in two dimensions: it grows the
+/// grid with fill values to fit the cell, if necessary.
+#[derive(Debug, Clone)]
+pub struct ExpandGrid<T, S = DefaultFill> {
+    storage: Storage<T>,
+    width: usize,
+    height: usize,
+    fill: S,
+}
+
+impl<T> ExpandGrid<T> {
+    /// Creates an empty grid with the [`Layout::Dense`] layout.
+    pub fn new() -> Self {
+        Self::with_layout_and_filler(Layout::Dense, DefaultFill)
+    }
+
+    /// Creates an empty grid with the [`Layout::Ragged`] layout.
+    pub fn ragged() -> Self {
+        Self::with_layout_and_filler(Layout::Ragged, DefaultFill)
+    }
+}
+
+impl<T: Clone> ExpandGrid<T, FillValue<T>> {
+    /// Creates an empty grid with the given `layout` that fills any gaps created by expansion with
+    /// clones of `value`.
+    pub fn with_fill(layout: Layout, value: T) -> Self {
+        Self::with_layout_and_filler(layout, FillValue(value))
+    }
+}
+
+impl<T, S> ExpandGrid<T, S> {
+    fn with_layout_and_filler(layout: Layout, fill: S) -> Self {
+        let storage = match layout {
+            Layout::Dense => Storage::Dense(Vec::new()),
+            Layout::Ragged => Storage::Ragged(Vec::new()),
+        };
+        Self {
+            storage,
+            width: 0,
+            height: 0,
+            fill,
+        }
+    }
+
+    /// Returns the layout in which the cells are stored.
+    pub fn layout(&self) -> Layout {
+        match self.storage {
+            Storage::Dense(_) => Layout::Dense,
+            Storage::Ragged(_) => Layout::Ragged,
+        }
+    }
+
+    /// Returns the filler that creates new cells when the grid expands.
+    pub fn filler(&self) -> &S {
+        &self.fill
+    }
+
+    /// Returns the number of columns, which is the length of the longest row.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns a reference to the cell at `(row, col)`, or `None` if it is out of bounds.
+    ///
+    /// In a [`Layout::Ragged`] grid, a cell beyond the end of its own row is out of bounds, even
+    /// if other rows are longer.
+    pub fn get(&self, (row, col): (usize, usize)) -> Option<&T> {
+        self.row(row)?.get(col)
+    }
+
+    /// Returns a mutable reference to the cell at `(row, col)`, or `None` if it is out of bounds.
+    pub fn get_mut(&mut self, (row, col): (usize, usize)) -> Option<&mut T> {
+        self.row_mut(row)?.get_mut(col)
+    }
+
+    /// Returns the cells of `row`, or `None` if the row is out of bounds.
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        match &self.storage {
+            Storage::Dense(cells) if row < self.height => {
+                Some(&cells[row * self.width..(row + 1) * self.width])
+            }
+            Storage::Dense(_) => None,
+            Storage::Ragged(rows) => rows.get(row).map(Vec::as_slice),
+        }
+    }
+
+    /// Returns the cells of `row` mutably, or `None` if the row is out of bounds.
+    pub fn row_mut(&mut self, row: usize) -> Option<&mut [T]> {
+        match &mut self.storage {
+            Storage::Dense(cells) if row < self.height => {
+                Some(&mut cells[row * self.width..(row + 1) * self.width])
+            }
+            Storage::Dense(_) => None,
+            Storage::Ragged(rows) => rows.get_mut(row).map(Vec::as_mut_slice),
+        }
+    }
+
+    /// Returns an iterator over the rows, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height).map(|row| self.row(row).unwrap())
+    }
+
+    /// Returns an iterator over the cells of column `col`, from top to bottom.
+    ///
+    /// Yields `None` for rows that do not reach `col`, which only occurs in a [`Layout::Ragged`]
+    /// grid or when `col` is beyond the [`width`](ExpandGrid::width).
+    pub fn column(&self, col: usize) -> impl Iterator<Item = Option<&T>> + '_ {
+        self.rows().map(move |row| row.get(col))
+    }
+
+    /// Returns an iterator over the columns, from left to right.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = Option<&T>> + '_> + '_ {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// Returns the rows as separate [`Vec`]s.
+    ///
+    /// For a [`Layout::Dense`] grid, every row has a length of [`width`](ExpandGrid::width).
+    pub fn raw_rows(self) -> Vec<Vec<T>> {
+        match self.storage {
+            Storage::Dense(cells) => {
+                let mut cells = cells.into_iter();
+                (0..self.height)
+                    .map(|_| cells.by_ref().take(self.width).collect())
+                    .collect()
+            }
+            Storage::Ragged(rows) => rows,
+        }
+    }
+}
+
+impl<T, S: Fill<T>> ExpandGrid<T, S> {
+    /// Always returns a mutable reference to the cell at `(row, col)`.
+    ///
+    /// If the cell lies beyond the stored rows or columns, the grid is expanded with fill values
+    /// to fit it. In a [`Layout::Dense`] grid, every row is widened to fit `col`. In a
+    /// [`Layout::Ragged`] grid, only `row` itself is widened, and missing rows in between are
+    /// added empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` or `col` is `usize::MAX`, or if the number of cells in a
+    /// [`Layout::Dense`] grid would overflow a `usize`. The grid is left unaltered in that case.
+    pub fn expand_get_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        let overflow = || -> usize { panic!("{}", ExpandError::IndexOverflow) };
+        let row_end = row.checked_add(1).unwrap_or_else(overflow);
+        let col_end = col.checked_add(1).unwrap_or_else(overflow);
+        let fill = &self.fill;
+        match &mut self.storage {
+            Storage::Dense(cells) => {
+                let width = self.width.max(col_end);
+                let height = self.height.max(row_end);
+                let len = height.checked_mul(width).unwrap_or_else(overflow);
+                if width > self.width {
+                    let old_width = mem::replace(&mut self.width, width);
+                    let mut old = mem::take(cells).into_iter();
+                    cells.reserve_exact(self.height * self.width);
+                    for _ in 0..self.height {
+                        cells.extend(old.by_ref().take(old_width));
+                        cells.extend((old_width..self.width).map(|_| fill.fill()));
+                    }
+                }
+                if height > self.height {
+                    self.height = height;
+                    cells.resize_with(len, || fill.fill());
+                }
+                &mut cells[row * self.width + col]
+            }
+            Storage::Ragged(rows) => {
+                if row >= self.height {
+                    self.height = row_end;
+                    rows.resize_with(self.height, Vec::new);
+                }
+                let cells = &mut rows[row];
+                if col >= cells.len() {
+                    cells.resize_with(col_end, || fill.fill());
+                    self.width = self.width.max(col_end);
+                }
+                &mut cells[col]
+            }
+        }
+    }
+}
+
+impl<T, S: Default> Default for ExpandGrid<T, S> {
+    fn default() -> Self {
+        Self::with_layout_and_filler(Layout::Dense, S::default())
+    }
+}
@@ -7,20 +7,326 @@
 //! collection. A mutable reference to that new item is returned. When the index does fit within the
 //! existing collection, a mutable reference to that item is simply returned, without altering the
 //! inner collection.
-use std::slice::SliceIndex;
+//!
+//! New items are created by a [`Fill`] strategy. By default, they are [`Default::default`], but
+//! [`ExpandVec::with_fill`] allows for filling gaps with clones of any value.
+//!
+//! An [`ExpandGrid`] provides the same expanding access in two dimensions, and an [`ExpandMap`]
+//! provides it for sparse collections that only store the items that have been written. An
+//! [`ExpandChunks`] stores its items in pages that are allocated on demand, so that growth never
+//! moves the items that are already stored. An [`ExpandDeque`] is indexed by [`isize`] and
+//! expands at either end. An [`ExpandRuns`] stores piecewise-constant data as runs of equal
+//! values.
+use std::borrow::{Borrow, BorrowMut};
+use std::collections::TryReserveError;
+use std::mem;
+use std::ops::{Bound, Deref, DerefMut, Index, IndexMut, Range, RangeBounds};
+use std::slice::{self, GetDisjointMutError, SliceIndex};
+use std::sync::OnceLock;
+use std::vec;
+
+mod auto;
+pub mod binary;
+mod chunks;
+mod deque;
+mod entry;
+mod error;
+mod fill;
+mod grid;
+mod map;
+mod occupancy;
+mod runs;
+#[cfg(feature = "serde")]
+pub mod serde;
+
+pub use auto::AutoExpand;
+pub use chunks::{ExpandChunks, DEFAULT_PAGE_SIZE};
+pub use deque::ExpandDeque;
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
+pub use error::ExpandError;
+pub use fill::{DefaultFill, Fill, FillValue};
+pub use grid::{ExpandGrid, Layout};
+pub use map::ExpandMap;
+pub use occupancy::{IterHoles, IterOccupied};
+pub use runs::ExpandRuns;
+
+use occupancy::Occupancy;
 
 /// A growable array that expands to provide a mutable reference to items beyond the stored
 /// collection.
 ///
-/// The inner collection is a regular [`Vec`].
+/// The inner collection is a regular [`Vec`]. New items created by expansion are produced by the
+/// filler `S`.
+///
+/// Expansion can be limited to a maximum length with [`ExpandVec::set_max_len`], to guard against
+/// indices that would otherwise consume all memory.
+///
+/// With [`ExpandVec::set_track_occupancy`], the collection records which slots were explicitly
+/// written and which ones were filled in as a gap.
+///
+/// Unlike a [`Vec<T>`], an `ExpandVec<T>` is only [`Sync`] if `T` is both [`Send`] and [`Sync`],
+/// because [`ExpandVec::get_or_default`] may create and cache a fill value through a shared
+/// reference.
 #[derive(Debug, Clone)]
-pub struct ExpandVec<T: Default + Clone> {
+pub struct ExpandVec<T, S = DefaultFill> {
     inner: Vec<T>,
+    fill: S,
+    /// The opt-in settings, or `None` if none of them are in use.
+    options: Option<Box<Options<T, S>>>,
+    /// A fill value to read out of bounds, created on first use if the filler does not hold one.
+    /// It is boxed, so that it only takes up space once it is created.
+    gap: OnceLock<Box<T>>,
+}
+
+/// The opt-in settings of an [`ExpandVec`]. They are boxed, so that a collection that does not
+/// use any of them stays close to the size of a [`Vec`].
+#[derive(Debug, Clone)]
+struct Options<T, S> {
+    max_len: Option<usize>,
+    /// Trims trailing fill values, if auto-trimming is enabled.
+    auto_trim: Option<fn(&mut ExpandVec<T, S>)>,
+    /// Records which slots were explicitly written, if occupancy tracking is enabled.
+    occupancy: Option<Occupancy>,
 }
 
-impl<T: Default + Clone> ExpandVec<T> {
+impl<T, S> Options<T, S> {
+    const NONE: Self = Self {
+        max_len: None,
+        auto_trim: None,
+        occupancy: None,
+    };
+
+    fn is_none(&self) -> bool {
+        self.max_len.is_none() && self.auto_trim.is_none() && self.occupancy.is_none()
+    }
+}
+
+impl<T> ExpandVec<T> {
     pub fn new() -> Self {
-        Self { inner: Vec::new() }
+        Self::with_filler(DefaultFill)
+    }
+
+    /// Creates an empty collection with at least the specified capacity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: Vec::with_capacity(capacity),
+            ..Self::new()
+        }
+    }
+}
+
+impl<T: Clone> ExpandVec<T, FillValue<T>> {
+    /// Creates an empty collection that fills any gaps created by expansion with clones of
+    /// `value`, rather than with [`Default::default`].
+    ///
+    /// This allows for using [`ExpandVec::expand_get_mut`] with types that do not implement
+    /// [`Default`], or for filling gaps with a sentinel value.
+    pub fn with_fill(value: T) -> Self {
+        Self::with_filler(FillValue(value))
+    }
+}
+
+impl<T, S> ExpandVec<T, S> {
+    fn with_filler(fill: S) -> Self {
+        Self {
+            inner: Vec::new(),
+            fill,
+            options: None,
+            gap: OnceLock::new(),
+        }
+    }
+
+    /// Returns the filler that creates new items when the collection expands.
+    pub fn filler(&self) -> &S {
+        &self.fill
+    }
+
+    /// Returns the maximum length the collection may be expanded to, if any.
+    pub fn max_len(&self) -> Option<usize> {
+        self.options.as_ref().and_then(|options| options.max_len)
+    }
+
+    /// Sets the maximum length the collection may be expanded to, or removes the limit if `None`.
+    ///
+    /// Expanding methods panic, and [`ExpandVec::try_expand_get_mut`] returns an error, rather
+    /// than expanding beyond this length. Items that are already stored are not affected.
+    ///
+    /// The limit only applies to expansion to fit an index or a range, so it is not a complete
+    /// guard against memory use. These methods grow the collection beyond it regardless:
+    /// [`push`](ExpandVec::push), [`try_push`](ExpandVec::try_push),
+    /// [`insert`](ExpandVec::insert), [`extend`](Extend::extend), [`resize`](ExpandVec::resize)
+    /// and [`resize_with`](ExpandVec::resize_with). Collections created through [`From`],
+    /// [`FromIterator`], [`ExpandVec::read_from`] or deserialization start out without a limit.
+    pub fn set_max_len(&mut self, max_len: Option<usize>) {
+        self.update_options(|options| options.max_len = max_len)
+    }
+
+    /// Changes the opt-in settings, allocating them if needed, and frees them again if none of
+    /// them remain in use.
+    fn update_options(&mut self, update: impl FnOnce(&mut Options<T, S>)) {
+        let options = self.options.get_or_insert_with(|| Box::new(Options::NONE));
+        update(options);
+        if options.is_none() {
+            self.options = None
+        }
+    }
+
+    /// Returns `true` if trailing fill values are trimmed automatically.
+    ///
+    /// See [`ExpandVec::set_auto_trim`].
+    pub fn is_auto_trim(&self) -> bool {
+        self.options
+            .as_ref()
+            .is_some_and(|options| options.auto_trim.is_some())
+    }
+
+    /// Trims trailing fill values if auto-trimming is enabled.
+    fn run_auto_trim(&mut self) {
+        if let Some(trim) = self.options.as_ref().and_then(|options| options.auto_trim) {
+            trim(self)
+        }
+    }
+
+    /// Enables or disables tracking of which slots were explicitly written.
+    ///
+    /// When enabled, a slot is marked as occupied when it is written through
+    /// [`push`](ExpandVec::push), [`insert`](ExpandVec::insert), [`extend`](Extend::extend),
+    /// [`resize`](ExpandVec::resize), [`set`](ExpandVec::set), any of the `expand_get` or `fill`
+    /// methods, or [`copy_from_slice_at`](ExpandVec::copy_from_slice_at). Slots that are created
+    /// to fill a gap are holes. All slots that are already stored
+    /// when tracking is enabled count as occupied.
+    ///
+    /// Writing through a reference to an existing slot, such as through
+    /// [`get_mut`](ExpandVec::get_mut) or a mutable slice, does not change its occupancy.
+    /// When tracking is disabled, every stored slot counts as occupied.
+    ///
+    /// While tracking is enabled, [auto-trimming](ExpandVec::set_auto_trim) keeps occupied slots,
+    /// even if they hold the fill value.
+    pub fn set_track_occupancy(&mut self, enabled: bool) {
+        let len = self.inner.len();
+        self.update_options(|options| {
+            if !enabled {
+                options.occupancy = None
+            } else if options.occupancy.is_none() {
+                options.occupancy = Some(Occupancy::with_len(len, true))
+            }
+        })
+    }
+
+    fn occupancy(&self) -> Option<&Occupancy> {
+        self.options.as_ref()?.occupancy.as_ref()
+    }
+
+    fn occupancy_mut(&mut self) -> Option<&mut Occupancy> {
+        self.options.as_mut()?.occupancy.as_mut()
+    }
+
+    /// Returns `true` if the collection tracks which slots were explicitly written.
+    ///
+    /// See [`ExpandVec::set_track_occupancy`].
+    pub fn is_tracking_occupancy(&self) -> bool {
+        self.occupancy().is_some()
+    }
+
+    /// Returns `true` if the slot at `index` was explicitly written, rather than filled in as a
+    /// gap. Returns `false` if `index` is out of bounds.
+    pub fn is_occupied(&self, index: usize) -> bool {
+        match self.occupancy() {
+            Some(occupancy) => occupancy.get(index),
+            None => index < self.inner.len(),
+        }
+    }
+
+    /// Returns the number of slots that were explicitly written.
+    pub fn occupied_count(&self) -> usize {
+        self.occupancy().map_or(self.inner.len(), Occupancy::count)
+    }
+
+    /// Returns an iterator over the indices of the slots that were explicitly written, in
+    /// ascending order.
+    ///
+    /// Empty stretches are skipped in large steps, so this is fast even for very sparse data.
+    pub fn iter_occupied(&self) -> IterOccupied<'_> {
+        IterOccupied::new(self.occupancy(), self.inner.len())
+    }
+
+    /// Returns an iterator over the indices of the slots that were filled in as a gap, in
+    /// ascending order.
+    pub fn iter_holes(&self) -> IterHoles<'_> {
+        IterHoles::new(self.occupancy())
+    }
+
+    /// Resizes the occupancy to the length of the inner collection, marking new slots as
+    /// occupied or as holes.
+    fn sync_occupancy(&mut self, occupied: bool) {
+        let len = self.inner.len();
+        if let Some(occupancy) = self.occupancy_mut() {
+            occupancy.resize(len, occupied)
+        }
+    }
+
+    /// Marks the slots in `range` as occupied.
+    fn occupy(&mut self, range: Range<usize>) {
+        if let Some(occupancy) = self.occupancy_mut() {
+            occupancy.occupy(range)
+        }
+    }
+
+    /// Checks whether the collection may be expanded to `len` items.
+    fn check_len(&self, len: usize) -> Result<(), ExpandError> {
+        match self.max_len() {
+            Some(max_len) if len > max_len => Err(ExpandError::OverLimit { len, max_len }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the length required to fit `index`, if the collection may be expanded to it.
+    fn len_to_fit(&self, index: usize) -> Result<usize, ExpandError> {
+        let len = index.checked_add(1).ok_or(ExpandError::IndexOverflow)?;
+        self.check_len(len)?;
+        Ok(len)
+    }
+
+    /// Returns the number of elements in the collection.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the collection contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the number of elements the collection can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more elements. See [`Vec::reserve`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    pub fn reserve(&mut self, additional: usize) {
+        self.inner.reserve(additional)
+    }
+
+    /// Reserves capacity for exactly `additional` more elements. See [`Vec::reserve_exact`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.inner.reserve_exact(additional)
+    }
+
+    /// Shrinks the capacity of the collection as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.inner.shrink_to_fit()
     }
 
     /// Appends an element to the back of a collection.
@@ -29,7 +335,113 @@ impl<T: Default + Clone> ExpandVec<T> {
     ///
     /// Panics if the new capacity exceeds `isize::MAX` bytes.
     pub fn push(&mut self, value: T) {
-        self.inner.push(value)
+        self.inner.push(value);
+        self.sync_occupancy(true)
+    }
+
+    /// Removes the last element and returns it, or `None` if the collection is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        let item = self.inner.pop();
+        self.sync_occupancy(false);
+        self.run_auto_trim();
+        item
+    }
+
+    /// Inserts an element at position `index`, shifting all elements after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, element: T) {
+        self.inner.insert(index, element);
+        if let Some(occupancy) = self.occupancy_mut() {
+            occupancy.insert(index, true)
+        }
+    }
+
+    /// Removes and returns the element at position `index`, shifting all elements after it to
+    /// the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        let item = self.inner.remove(index);
+        if let Some(occupancy) = self.occupancy_mut() {
+            occupancy.remove(index)
+        }
+        self.run_auto_trim();
+        item
+    }
+
+    /// Removes and returns the element at position `index`, replacing it with the last element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let item = self.inner.swap_remove(index);
+        if let Some(occupancy) = self.occupancy_mut() {
+            occupancy.swap_remove(index)
+        }
+        self.run_auto_trim();
+        item
+    }
+
+    /// Shortens the collection to the first `len` elements, dropping the rest.
+    /// If `len` is greater than or equal to the current length, this has no effect.
+    pub fn truncate(&mut self, len: usize) {
+        self.inner.truncate(len);
+        self.sync_occupancy(false);
+        self.run_auto_trim();
+    }
+
+    /// Removes all elements, keeping the allocated capacity.
+    pub fn clear(&mut self) {
+        self.inner.clear();
+        self.sync_occupancy(false)
+    }
+
+    /// Resizes the collection in place to `new_len` elements, filling new slots with clones of
+    /// `value` or truncating as needed. See [`Vec::resize`].
+    ///
+    /// Unlike the expanding methods, this is not bound by the [`max_len`](ExpandVec::max_len).
+    pub fn resize(&mut self, new_len: usize, value: T)
+    where
+        T: Clone,
+    {
+        self.inner.resize(new_len, value);
+        self.sync_occupancy(true)
+    }
+
+    /// Resizes the collection in place to `new_len` elements, filling new slots with the values
+    /// returned by `f` or truncating as needed. See [`Vec::resize_with`].
+    ///
+    /// Unlike the expanding methods, this is not bound by the [`max_len`](ExpandVec::max_len).
+    pub fn resize_with<F>(&mut self, new_len: usize, f: F)
+    where
+        F: FnMut() -> T,
+    {
+        self.inner.resize_with(new_len, f);
+        self.sync_occupancy(true)
+    }
+
+    /// Appends an element to the back of a collection, returning an error rather than aborting
+    /// if the memory for it cannot be allocated.
+    ///
+    /// On error, `value` is dropped and the collection is left unaltered.
+    pub fn try_push(&mut self, value: T) -> Result<(), TryReserveError> {
+        self.inner.try_reserve(1)?;
+        self.push(value);
+        Ok(())
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements, returning an error
+    /// rather than aborting if the memory cannot be allocated.
+    ///
+    /// On error, the collection is left unaltered. See [`Vec::try_reserve`].
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.inner.try_reserve(additional)
     }
 
     /// Returns a reference to an element or subslice depending on the type of index.
@@ -54,27 +466,607 @@ impl<T: Default + Clone> ExpandVec<T> {
         self.inner.get_mut(index)
     }
 
+    /// Always returns a mutable reference to an element.
+    /// If the index points beyond the contents of the inner collection, it is expanded to fit the
+    /// index, calling `f` with the index of each new item to create it. In that case, a mutable
+    /// reference to this last item under the index is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is `usize::MAX` or the expansion would exceed the
+    /// [`max_len`](ExpandVec::max_len).
+    pub fn expand_get_mut_with<F>(&mut self, index: usize, f: F) -> &mut T
+    where
+        F: FnMut(usize) -> T,
+    {
+        let len = index
+            .checked_add(1)
+            .unwrap_or_else(|| panic!("{}", ExpandError::IndexOverflow));
+        self.expand_to_with(len, f);
+        self.occupy(index..len);
+        // We can safely unwrap since the inner Vec was extended by a sufficient number of items.
+        self.inner.get_mut(index).unwrap()
+    }
+
+    /// Expands the inner collection to hold at least `len` items, calling `f` with the index of
+    /// each new item to create it.
+    /// If the collection already holds `len` or more items, it is left unaltered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the expansion would exceed the [`max_len`](ExpandVec::max_len).
+    pub fn expand_to_with<F>(&mut self, len: usize, f: F)
+    where
+        F: FnMut(usize) -> T,
+    {
+        let start = self.inner.len();
+        if len > start {
+            if let Err(err) = self.check_len(len) {
+                panic!("{err}")
+            }
+            self.inner.extend((start..len).map(f));
+            self.sync_occupancy(false)
+        }
+    }
+
+    /// Returns an iterator over the elements.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.inner.iter()
+    }
+
+    /// Returns an iterator that allows modifying each element.
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
+        self.inner.iter_mut()
+    }
+
+    /// Returns a view on the collection whose [`IndexMut`] implementation expands like
+    /// [`ExpandVec::expand_get_mut`].
+    ///
+    /// Plain indexing of an [`ExpandVec`] never expands, so this makes expanding writes explicit.
+    ///
+    /// ```
+    /// # use expand_vec::ExpandVec;
+    /// let mut v = ExpandVec::new();
+    /// v.auto()[2] = 7;
+    /// assert_eq!(v[..], [0, 0, 7]);
+    /// ```
+    pub fn auto(&mut self) -> AutoExpand<'_, T, S> {
+        AutoExpand::new(self)
+    }
+
+    /// Returns the inner [`Vec`].
+    pub fn raw_vec(self) -> Vec<T> {
+        self.inner
+    }
+}
+
+impl<T, S: Fill<T>> ExpandVec<T, S> {
     /// Always returns a mutable reference to an element.
     /// If the index points beyond the contents of the inner collection, it is expanded with
-    /// default values to fit the index. In that case, a mutable reference to this last item under
+    /// fill values to fit the index. In that case, a mutable reference to this last item under
     /// the index is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is `usize::MAX` or the expansion would exceed the
+    /// [`max_len`](ExpandVec::max_len). See [`ExpandVec::try_expand_get_mut`] for a fallible
+    /// version.
     pub fn expand_get_mut(&mut self, index: usize) -> &mut T {
-        if index > self.inner.len() {
-            let remaining = index - self.inner.len();
-            self.inner.extend(vec![Default::default(); remaining])
+        if index >= self.inner.len() {
+            let len = self.len_to_fit(index).unwrap_or_else(|err| panic!("{err}"));
+            self.inner.resize_with(len, || self.fill.fill());
+            self.sync_occupancy(false)
         }
+        self.occupy(index..index + 1);
         // We can safely unwrap since the inner Vec was extended by a sufficient number of items.
         self.inner.get_mut(index).unwrap()
     }
 
-    /// Returns the inner [`Vec`].
-    pub fn raw_vec(self) -> Vec<T> {
+    /// Returns mutable references to the elements at all `indices` at once, expanding the inner
+    /// collection with fill values to fit the largest index if necessary.
+    ///
+    /// The collection is expanded in a single step, like [`ExpandVec::expand_get_mut`] for the
+    /// largest index. Returns [`GetDisjointMutError::OverlappingIndices`] if any index occurs more
+    /// than once, in which case the collection is left unaltered. See
+    /// [`get_disjoint_mut`](prim@slice#method.get_disjoint_mut).
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index is `usize::MAX` or the expansion would exceed the
+    /// [`max_len`](ExpandVec::max_len).
+    pub fn expand_get_many_mut<const N: usize>(
+        &mut self,
+        indices: [usize; N],
+    ) -> Result<[&mut T; N], GetDisjointMutError> {
+        for (i, index) in indices.iter().enumerate() {
+            if indices[..i].contains(index) {
+                return Err(GetDisjointMutError::OverlappingIndices);
+            }
+        }
+        if let Some(&max) = indices.iter().max() {
+            self.expand_get_mut(max);
+        }
+        for &index in &indices {
+            self.occupy(index..index + 1);
+        }
+        self.inner.get_disjoint_mut(indices)
+    }
+
+    /// Returns a reference to the element at `index`, or to a shared fill value if the index is
+    /// out of bounds.
+    ///
+    /// This reads every index as if the collection had been expanded to fit it, without
+    /// expanding it. The fill value is [`Default::default`] unless the collection was created
+    /// with [`ExpandVec::with_fill`], in which case the value held by the filler is returned.
+    /// Otherwise, it is created on the first out of bounds read, and kept for later ones.
+    pub fn get_or_default(&self, index: usize) -> &T {
+        match self.inner.get(index) {
+            Some(item) => item,
+            None => match self.fill.fill_ref() {
+                Some(fill) => fill,
+                None => self.gap.get_or_init(|| Box::new(self.fill.fill())),
+            },
+        }
+    }
+
+    /// Returns a mutable reference to an element, expanding the inner collection with fill
+    /// values to fit the index if necessary, like [`ExpandVec::expand_get_mut`].
+    ///
+    /// Rather than panicking or aborting, an [`ExpandError`] is returned if the index is
+    /// `usize::MAX`, if the expansion would exceed the [`max_len`](ExpandVec::max_len), or if the
+    /// memory for the expansion cannot be allocated. In that case, the collection is left
+    /// unaltered.
+    pub fn try_expand_get_mut(&mut self, index: usize) -> Result<&mut T, ExpandError> {
+        if index >= self.inner.len() {
+            let len = self.len_to_fit(index)?;
+            self.inner.try_reserve(len - self.inner.len())?;
+            self.inner.resize_with(len, || self.fill.fill());
+            self.sync_occupancy(false)
+        }
+        self.occupy(index..index + 1);
+        // We can safely unwrap since the inner Vec was extended by a sufficient number of items.
+        Ok(self.inner.get_mut(index).unwrap())
+    }
+
+    /// Stores `value` at `index`, expanding the inner collection with fill values to fit the
+    /// index if necessary.
+    ///
+    /// Returns the previous value as `Some` if the slot already existed, or `None` if it had to be
+    /// created. A created slot is never filled before `value` is stored in it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is `usize::MAX` or the expansion would exceed the
+    /// [`max_len`](ExpandVec::max_len).
+    pub fn set(&mut self, index: usize, value: T) -> Option<T> {
+        let old = self.store(index, value);
+        self.run_auto_trim();
+        old
+    }
+
+    /// Stores `value` at `index` like [`ExpandVec::set`], but without auto-trimming.
+    fn store(&mut self, index: usize, value: T) -> Option<T> {
+        let old = match self.inner.get_mut(index) {
+            Some(item) => Some(mem::replace(item, value)),
+            None => {
+                let len = self.len_to_fit(index).unwrap_or_else(|err| panic!("{err}"));
+                self.inner.reserve(len - self.inner.len());
+                self.inner.resize_with(index, || self.fill.fill());
+                self.inner.push(value);
+                self.sync_occupancy(false);
+                None
+            }
+        };
+        self.occupy(index..index + 1);
+        old
+    }
+
+    /// Returns the entry for the slot at `index`, for in-place manipulation.
+    ///
+    /// The entry is vacant if `index` is out of bounds, or if occupancy is tracked and the slot is
+    /// a hole (see [`ExpandVec::set_track_occupancy`]). Looking up an entry never expands the
+    /// collection; only inserting into a vacant entry does.
+    pub fn entry(&mut self, index: usize) -> Entry<'_, T, S> {
+        if self.is_occupied(index) {
+            Entry::Occupied(OccupiedEntry::new(self, index))
+        } else {
+            Entry::Vacant(VacantEntry::new(self, index))
+        }
+    }
+
+    /// Stores `value` at `index`, expanding if necessary, and returns the previous value if the
+    /// slot already existed.
+    ///
+    /// This is the same as [`ExpandVec::set`], named after [`mem::replace`].
+    pub fn replace(&mut self, index: usize, value: T) -> Option<T> {
+        self.set(index, value)
+    }
+
+    /// Always returns a mutable reference to the subslice covered by `range`.
+    /// If the range reaches beyond the contents of the inner collection, it is expanded with
+    /// fill values to fit the end of the range.
+    ///
+    /// Both exclusive (`a..b`, `..b`) and inclusive (`a..=b`, `..=b`) ranges are accepted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the end of the range is unbounded (`a..` or `..`), if the start of the range is
+    /// greater than its end, if an inclusive end is `usize::MAX`, or if the expansion would
+    /// exceed the [`max_len`](ExpandVec::max_len).
+    pub fn expand_get_range_mut<R>(&mut self, range: R) -> &mut [T]
+    where
+        R: RangeBounds<usize>,
+    {
+        let Range { start, end } = expand_range(range);
+        if end > self.inner.len() {
+            if let Err(err) = self.check_len(end) {
+                panic!("{err}")
+            }
+            self.inner.resize_with(end, || self.fill.fill());
+            self.sync_occupancy(false)
+        }
+        self.occupy(start..end);
+        &mut self.inner[start..end]
+    }
+
+    /// Expands the inner collection with fill values to hold at least `len` items, growing the
+    /// storage in a single step.
+    /// If the collection already holds `len` or more items, it is left unaltered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the expansion would exceed the [`max_len`](ExpandVec::max_len).
+    pub fn expand_to(&mut self, len: usize) {
+        if len > self.inner.len() {
+            if let Err(err) = self.check_len(len) {
+                panic!("{err}")
+            }
+            self.inner.resize_with(len, || self.fill.fill());
+            self.sync_occupancy(false)
+        }
+    }
+
+    /// Stores clones of `value` at every index in `range`, expanding the inner collection with
+    /// fill values to fit the end of the range if necessary.
+    ///
+    /// See [`ExpandVec::fill_with`] for the details. To fill only the existing items, use the
+    /// slice method instead, as in `v[..].fill(value)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`ExpandVec::fill_with`].
+    pub fn fill<R>(&mut self, range: R, value: T)
+    where
+        R: RangeBounds<usize>,
+        T: Clone,
+    {
+        self.fill_with(range, |_| value.clone())
+    }
+
+    /// Stores the result of calling `f` with each index in `range` at that index, in index order,
+    /// expanding the inner collection to fit the end of the range if necessary.
+    ///
+    /// The storage grows in a single step. Any gap between the previous end of the collection and
+    /// the start of the range is filled with fill values, while the slots in the range are never
+    /// filled before they are written. Both exclusive (`a..b`, `..b`) and inclusive (`a..=b`,
+    /// `..=b`) ranges are accepted.
+    ///
+    /// ```
+    /// # use expand_vec::ExpandVec;
+    /// let mut v = ExpandVec::<usize>::from([1, 2]);
+    /// v.fill_with(1..4, |i| i * 10);
+    /// assert_eq!(v[..], [1, 10, 20, 30]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the end of the range is unbounded (`a..` or `..`), if the start of the range is
+    /// greater than its end, if an inclusive end is `usize::MAX`, or if the expansion would
+    /// exceed the [`max_len`](ExpandVec::max_len).
+    pub fn fill_with<R, F>(&mut self, range: R, mut f: F)
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(usize) -> T,
+    {
+        let Range { start, end } = expand_range(range);
+        let len = self.inner.len();
+        if end > len {
+            if let Err(err) = self.check_len(end) {
+                panic!("{err}")
+            }
+        }
+        for index in start..end.min(len) {
+            self.inner[index] = f(index)
+        }
+        if end > len {
+            self.inner.reserve(end - len);
+            self.inner.resize_with(start.max(len), || self.fill.fill());
+            self.inner.extend((start.max(len)..end).map(f));
+            self.sync_occupancy(false)
+        }
+        self.occupy(start..end);
+        self.run_auto_trim();
+    }
+
+    /// Copies all items of `src` into the collection, starting at `offset`, expanding the inner
+    /// collection to fit them if necessary.
+    ///
+    /// Like [`ExpandVec::fill_with`], the storage grows in a single step, and any gap before
+    /// `offset` is filled with fill values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the end of the copied items would overflow a `usize`, or if the expansion would
+    /// exceed the [`max_len`](ExpandVec::max_len).
+    pub fn copy_from_slice_at(&mut self, offset: usize, src: &[T])
+    where
+        T: Copy,
+    {
+        let end = offset
+            .checked_add(src.len())
+            .unwrap_or_else(|| panic!("{}", ExpandError::IndexOverflow));
+        let len = self.inner.len();
+        if end > len {
+            if let Err(err) = self.check_len(end) {
+                panic!("{err}")
+            }
+        }
+        let (overwritten, appended) = src.split_at(len.saturating_sub(offset).min(src.len()));
+        if !overwritten.is_empty() {
+            self.inner[offset..offset + overwritten.len()].copy_from_slice(overwritten);
+        }
+        if end > len {
+            self.inner.reserve(end - len);
+            self.inner.resize_with(offset.max(len), || self.fill.fill());
+            self.inner.extend_from_slice(appended);
+            self.sync_occupancy(false)
+        }
+        self.occupy(offset..end);
+        self.run_auto_trim();
+    }
+}
+
+impl<T: PartialEq, S: Fill<T>> ExpandVec<T, S> {
+    /// Returns the length of the collection without its trailing run of fill values.
+    ///
+    /// This is the length that [`ExpandVec::trim_trailing_defaults`] would truncate to.
+    pub fn logical_len(&self) -> usize {
+        let fill = self.fill.fill();
         self.inner
+            .iter()
+            .rposition(|item| *item != fill)
+            .map_or(0, |index| index + 1)
+    }
+
+    /// Removes the trailing run of items that are equal to the fill value, which is
+    /// [`Default::default`] unless the collection was created with [`ExpandVec::with_fill`].
+    ///
+    /// Two collections holding the same items up to their trailing fill values hold exactly the
+    /// same items afterwards. The allocated capacity is kept; see [`ExpandVec::shrink_to_fit`].
+    pub fn trim_trailing_defaults(&mut self) {
+        let len = self.logical_len();
+        // Not `self.truncate`, which auto-trims, and would call back into this method.
+        self.inner.truncate(len);
+        self.sync_occupancy(false)
+    }
+
+    /// Removes the trailing run of items that are equal to the fill value and were not explicitly
+    /// written, as recorded by occupancy tracking. Without tracking, this is the same as
+    /// [`ExpandVec::trim_trailing_defaults`].
+    fn trim_trailing_holes(&mut self) {
+        let fill = self.fill.fill();
+        let occupancy = self.occupancy();
+        let len = (0..self.inner.len())
+            .rposition(|index| {
+                self.inner[index] != fill || occupancy.is_some_and(|occupancy| occupancy.get(index))
+            })
+            .map_or(0, |index| index + 1);
+        self.inner.truncate(len);
+        self.sync_occupancy(false)
+    }
+
+    /// Enables or disables auto-trimming of trailing fill values.
+    ///
+    /// When enabled, the collection is trimmed right away, and again after every
+    /// [`set`](ExpandVec::set), [`replace`](ExpandVec::replace), [`pop`](ExpandVec::pop),
+    /// [`remove`](ExpandVec::remove), [`swap_remove`](ExpandVec::swap_remove),
+    /// [`truncate`](ExpandVec::truncate), [`fill`](ExpandVec::fill),
+    /// [`fill_with`](ExpandVec::fill_with) and
+    /// [`copy_from_slice_at`](ExpandVec::copy_from_slice_at). Items that are
+    /// written through a reference, such as the one returned by [`ExpandVec::expand_get_mut`],
+    /// cannot be observed, and are only trimmed by the next of these operations.
+    ///
+    /// If [occupancy tracking](ExpandVec::set_track_occupancy) is enabled, only trailing holes
+    /// that hold the fill value are trimmed, so that an explicitly written fill value is kept.
+    /// An explicit call to [`ExpandVec::trim_trailing_defaults`] still trims them all.
+    pub fn set_auto_trim(&mut self, enabled: bool) {
+        if enabled {
+            self.update_options(|options| options.auto_trim = Some(Self::trim_trailing_holes));
+            self.trim_trailing_holes()
+        } else {
+            self.update_options(|options| options.auto_trim = None)
+        }
+    }
+}
+
+impl<T, S, I: SliceIndex<[T]>> Index<I> for ExpandVec<T, S> {
+    type Output = I::Output;
+
+    /// Returns a reference to an element or subslice, like indexing a slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds. Indexing never expands the collection.
+    fn index(&self, index: I) -> &I::Output {
+        &self.inner[index]
+    }
+}
+
+impl<T, S, I: SliceIndex<[T]>> IndexMut<I> for ExpandVec<T, S> {
+    /// Returns a mutable reference to an element or subslice, like indexing a slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds. Indexing never expands the collection; use
+    /// [`ExpandVec::auto`] or [`ExpandVec::expand_get_mut`] for that.
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        &mut self.inner[index]
+    }
+}
+
+impl<T, S> Deref for ExpandVec<T, S> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.inner
+    }
+}
+
+impl<T, S> DerefMut for ExpandVec<T, S> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.inner
+    }
+}
+
+impl<T, S> AsRef<[T]> for ExpandVec<T, S> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, S> AsMut<[T]> for ExpandVec<T, S> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T, S> Borrow<[T]> for ExpandVec<T, S> {
+    fn borrow(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, S> BorrowMut<[T]> for ExpandVec<T, S> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self
     }
 }
 
-impl<T: Default + Clone> Default for ExpandVec<T> {
+impl<T, S: Default> From<Vec<T>> for ExpandVec<T, S> {
+    /// Wraps a [`Vec`] without copying its items.
+    fn from(inner: Vec<T>) -> Self {
+        Self {
+            inner,
+            ..Self::default()
+        }
+    }
+}
+
+impl<T, S: Default, const N: usize> From<[T; N]> for ExpandVec<T, S> {
+    fn from(items: [T; N]) -> Self {
+        Self::from(Vec::from(items))
+    }
+}
+
+impl<T: Clone, S: Default> From<&[T]> for ExpandVec<T, S> {
+    fn from(items: &[T]) -> Self {
+        Self::from(items.to_vec())
+    }
+}
+
+impl<T, S> From<ExpandVec<T, S>> for Vec<T> {
+    /// Returns the inner [`Vec`], like [`ExpandVec::raw_vec`].
+    fn from(vec: ExpandVec<T, S>) -> Self {
+        vec.raw_vec()
+    }
+}
+
+impl<T, S> From<ExpandVec<T, S>> for Box<[T]> {
+    fn from(vec: ExpandVec<T, S>) -> Self {
+        vec.raw_vec().into_boxed_slice()
+    }
+}
+
+impl<T, S> IntoIterator for ExpandVec<T, S> {
+    type Item = T;
+    type IntoIter = vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+impl<'a, T, S> IntoIterator for &'a ExpandVec<T, S> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, S> IntoIterator for &'a mut ExpandVec<T, S> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, S: Default> FromIterator<T> for ExpandVec<T, S> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            inner: Vec::from_iter(iter),
+            ..Self::default()
+        }
+    }
+}
+
+impl<T, S> Extend<T> for ExpandVec<T, S> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.inner.extend(iter);
+        self.sync_occupancy(true)
+    }
+}
+
+impl<'a, T: Copy + 'a, S> Extend<&'a T> for ExpandVec<T, S> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.inner.extend(iter);
+        self.sync_occupancy(true)
+    }
+}
+
+impl<T, S: Default> Default for ExpandVec<T, S> {
     fn default() -> Self {
-        Self::new()
+        Self::with_filler(S::default())
     }
 }
+
+/// Resolves the bounds of a range that a collection expands to fit.
+///
+/// # Panics
+///
+/// Panics if the end of the range is unbounded, if the start of the range is greater than its end,
+/// or if an inclusive end is `usize::MAX`.
+pub(crate) fn expand_range<R: RangeBounds<usize>>(range: R) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start
+            .checked_add(1)
+            .expect("attempted to expand to a range starting after usize::MAX"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end
+            .checked_add(1)
+            .expect("attempted to expand to a range ending at usize::MAX"),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => panic!("cannot expand to a range with an unbounded end"),
+    };
+    assert!(
+        start <= end,
+        "range start index {start} is greater than range end index {end}"
+    );
+    start..end
+}
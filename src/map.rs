@@ -0,0 +1,174 @@
+//! A sparse collection that expands like an [`ExpandVec`], but only stores the items that have
+//! been written.
+use std::collections::btree_map::{self, BTreeMap};
+
+use crate::{DefaultFill, ExpandError, ExpandVec, Fill, FillValue};
+
+/// A sparse collection that expands to provide a mutable reference to items beyond the stored
+/// collection.
+///
+/// Only the items that have been written are stored, in a [`BTreeMap`] keyed by index. Every other
+/// index below [`len`](ExpandMap::len) reads as the fill value. This makes it safe to expand to
+/// very large or scattered indices, such as external IDs, where an [`ExpandVec`] would allocate
+/// every item up to the index.
+#[derive(Debug, Clone)]
+pub struct ExpandMap<T, S = DefaultFill> {
+    slots: BTreeMap<usize, T>,
+    len: usize,
+    /// The value that is read for every slot that has not been written.
+    gap: T,
+    fill: S,
+}
+
+impl<T: Default> ExpandMap<T> {
+    pub fn new() -> Self {
+        Self::with_filler(DefaultFill)
+    }
+}
+
+impl<T: Clone> ExpandMap<T, FillValue<T>> {
+    /// Creates an empty collection that reads and fills unwritten slots with clones of `value`,
+    /// rather than with [`Default::default`].
+    pub fn with_fill(value: T) -> Self {
+        Self::with_filler(FillValue(value))
+    }
+}
+
+impl<T, S: Fill<T>> ExpandMap<T, S> {
+    fn with_filler(fill: S) -> Self {
+        Self {
+            slots: BTreeMap::new(),
+            len: 0,
+            gap: fill.fill(),
+            fill,
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index`, or `None` if it is out of bounds.
+    ///
+    /// If the slot has not been written yet, it is stored with a fill value first.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len {
+            return None;
+        }
+        Some(self.slots.entry(index).or_insert_with(|| self.fill.fill()))
+    }
+
+    /// Always returns a mutable reference to an element.
+    /// If the index points beyond the length of the collection, the length is expanded to fit the
+    /// index. Only the slot under the index itself is stored; the gap before it is not.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is `usize::MAX`.
+    pub fn expand_get_mut(&mut self, index: usize) -> &mut T {
+        if index >= self.len {
+            self.len = index
+                .checked_add(1)
+                .unwrap_or_else(|| panic!("{}", ExpandError::IndexOverflow));
+        }
+        self.slots.entry(index).or_insert_with(|| self.fill.fill())
+    }
+}
+
+impl<T, S> ExpandMap<T, S> {
+    /// Returns the filler that creates new items when the collection expands.
+    pub fn filler(&self) -> &S {
+        &self.fill
+    }
+
+    /// Returns the number of elements, including the unwritten ones.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the collection contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of slots that are actually stored.
+    pub fn stored_len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Appends an element to the back of a collection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length is already `usize::MAX`.
+    pub fn push(&mut self, value: T) {
+        let len = self
+            .len
+            .checked_add(1)
+            .unwrap_or_else(|| panic!("{}", ExpandError::IndexOverflow));
+        self.slots.insert(self.len, value);
+        self.len = len;
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if it is out of bounds.
+    ///
+    /// Slots that have not been written read as the fill value.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        Some(self.slots.get(&index).unwrap_or(&self.gap))
+    }
+
+    /// Returns an iterator over all elements in index order, including the unwritten ones.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        (0..self.len).map(|index| self.slots.get(&index).unwrap_or(&self.gap))
+    }
+
+    /// Returns an iterator over the stored slots and their indices, in index order.
+    pub fn iter_stored(&self) -> btree_map::Iter<'_, usize, T> {
+        self.slots.iter()
+    }
+
+    /// Returns a mutable iterator over the stored slots and their indices, in index order.
+    pub fn iter_stored_mut(&mut self) -> btree_map::IterMut<'_, usize, T> {
+        self.slots.iter_mut()
+    }
+}
+
+impl<T, S: Fill<T> + Default> Default for ExpandMap<T, S> {
+    fn default() -> Self {
+        Self::with_filler(S::default())
+    }
+}
+
+impl<T: PartialEq, S: Fill<T>> From<ExpandVec<T, S>> for ExpandMap<T, S> {
+    /// Converts an [`ExpandVec`] into a sparse collection of the same length, storing only the
+    /// items that differ from the fill value.
+    fn from(vec: ExpandVec<T, S>) -> Self {
+        let mut map = Self::with_filler(vec.fill);
+        map.len = vec.inner.len();
+        map.slots = vec
+            .inner
+            .into_iter()
+            .enumerate()
+            .filter(|(_, item)| *item != map.gap)
+            .collect();
+        map
+    }
+}
+
+impl<T, S: Fill<T>> From<ExpandMap<T, S>> for ExpandVec<T, S> {
+    /// Converts a sparse collection into an [`ExpandVec`] of the same length, materializing every
+    /// unwritten slot with a fill value.
+    fn from(map: ExpandMap<T, S>) -> Self {
+        let mut slots = map.slots.into_iter().peekable();
+        let fill = map.fill;
+        let inner = (0..map.len)
+            .map(|index| match slots.next_if(|(i, _)| *i == index) {
+                Some((_, item)) => item,
+                None => fill.fill(),
+            })
+            .collect();
+        Self {
+            inner,
+            ..Self::with_filler(fill)
+        }
+    }
+}
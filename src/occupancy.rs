@@ -0,0 +1,312 @@
+//! Tracking of which slots of an [`ExpandVec`](crate::ExpandVec) were explicitly written, as
+//! opposed to filled in as a gap.
+use std::ops::Range;
+
+const BITS: usize = u64::BITS as usize;
+
+/// Returns a mask of the bits below `bit`.
+fn low_mask(bit: usize) -> u64 {
+    (1 << bit) - 1
+}
+
+/// A two-level bitmap that records which slots are occupied.
+///
+/// Every bit in `summary` records whether the corresponding word in `words` has any bit set, so
+/// that iterating the occupied slots of sparse data skips 4096 empty slots per summary word.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Occupancy {
+    words: Vec<u64>,
+    summary: Vec<u64>,
+    len: usize,
+    count: usize,
+}
+
+impl Occupancy {
+    /// Creates a bitmap of `len` slots that are all `occupied`, or all holes.
+    pub(crate) fn with_len(len: usize, occupied: bool) -> Self {
+        let mut occupancy = Self::default();
+        occupancy.resize(len, occupied);
+        occupancy
+    }
+
+    pub(crate) fn count(&self) -> usize {
+        self.count
+    }
+
+    pub(crate) fn get(&self, index: usize) -> bool {
+        index < self.len && self.words[index / BITS] & (1 << (index % BITS)) != 0
+    }
+
+    /// Marks the slot at `index`, which must be in bounds, as occupied or as a hole.
+    pub(crate) fn set(&mut self, index: usize, occupied: bool) {
+        debug_assert!(index < self.len);
+        let (word, bit) = (index / BITS, index % BITS);
+        let old = self.words[word];
+        let new = if occupied {
+            old | 1 << bit
+        } else {
+            old & !(1 << bit)
+        };
+        if old != new {
+            self.words[word] = new;
+            if occupied {
+                self.count += 1;
+            } else {
+                self.count -= 1;
+            }
+            self.update_summary(word);
+        }
+    }
+
+    /// Marks all slots in `range`, which must be in bounds, as occupied.
+    pub(crate) fn occupy(&mut self, range: Range<usize>) {
+        for index in range {
+            self.set(index, true)
+        }
+    }
+
+    /// Resizes the bitmap to `len` slots, marking new slots as occupied or as holes.
+    pub(crate) fn resize(&mut self, len: usize, occupied: bool) {
+        if len <= self.len {
+            let words = len.div_ceil(BITS);
+            self.count -= self.words[words..]
+                .iter()
+                .map(|bits| bits.count_ones() as usize)
+                .sum::<usize>();
+            self.words.truncate(words);
+            if !len.is_multiple_of(BITS) {
+                let old = self.words[words - 1];
+                self.words[words - 1] = old & low_mask(len % BITS);
+                self.count -= (old & !low_mask(len % BITS)).count_ones() as usize;
+            }
+            self.len = len;
+            self.summary.truncate(words.div_ceil(BITS));
+            if !words.is_multiple_of(BITS) {
+                self.summary[words / BITS] &= low_mask(words % BITS);
+            }
+            if words > 0 {
+                self.update_summary(words - 1);
+            }
+        } else {
+            let start = self.len;
+            self.len = len;
+            self.words.resize(len.div_ceil(BITS), 0);
+            self.summary.resize(self.words.len().div_ceil(BITS), 0);
+            if occupied {
+                self.occupy(start..len);
+            }
+        }
+    }
+
+    /// Inserts a slot at `index`, shifting all slots after it up by one.
+    pub(crate) fn insert(&mut self, index: usize, occupied: bool) {
+        debug_assert!(index <= self.len);
+        self.resize(self.len + 1, false);
+        let (first, bit) = (index / BITS, index % BITS);
+        for word in (first + 1..self.words.len()).rev() {
+            self.words[word] = self.words[word] << 1 | self.words[word - 1] >> (BITS - 1);
+        }
+        let old = self.words[first];
+        self.words[first] = old & low_mask(bit) | (old & !low_mask(bit)) << 1;
+        self.words[first] |= u64::from(occupied) << bit;
+        self.rebuild();
+    }
+
+    /// Removes the slot at `index`, shifting all slots after it down by one.
+    pub(crate) fn remove(&mut self, index: usize) {
+        debug_assert!(index < self.len);
+        let (first, bit) = (index / BITS, index % BITS);
+        let old = self.words[first];
+        let upper = if bit == BITS - 1 {
+            0
+        } else {
+            old >> (bit + 1) << bit
+        };
+        self.words[first] = old & low_mask(bit) | upper;
+        for word in first..self.words.len() {
+            if word > first {
+                self.words[word] >>= 1;
+            }
+            if let Some(&next) = self.words.get(word + 1) {
+                self.words[word] |= next << (BITS - 1);
+            }
+        }
+        self.len -= 1;
+        self.words.truncate(self.len.div_ceil(BITS));
+        self.rebuild();
+    }
+
+    /// Removes the slot at `index`, replacing it with the last slot.
+    pub(crate) fn swap_remove(&mut self, index: usize) {
+        debug_assert!(index < self.len);
+        let last = self.get(self.len - 1);
+        self.set(index, last);
+        self.resize(self.len - 1, false);
+    }
+
+    /// Returns an iterator over the indices of the occupied slots.
+    pub(crate) fn iter_occupied(&self) -> OccupiedBits<'_> {
+        OccupiedBits {
+            occupancy: self,
+            summary: 0,
+            summary_bits: self.summary.first().copied().unwrap_or(0),
+            word: 0,
+            word_bits: 0,
+        }
+    }
+
+    /// Returns an iterator over the indices of the holes.
+    pub(crate) fn iter_holes(&self) -> HoleBits<'_> {
+        HoleBits {
+            occupancy: self,
+            word: 0,
+            word_bits: self.hole_bits(0),
+        }
+    }
+
+    /// Returns the holes in `word` as set bits, ignoring the bits beyond the length.
+    fn hole_bits(&self, word: usize) -> u64 {
+        match self.words.get(word) {
+            Some(&bits) if (word + 1) * BITS > self.len => !bits & low_mask(self.len % BITS),
+            Some(&bits) => !bits,
+            None => 0,
+        }
+    }
+
+    fn update_summary(&mut self, word: usize) {
+        let (summary, bit) = (word / BITS, word % BITS);
+        if self.words[word] == 0 {
+            self.summary[summary] &= !(1 << bit);
+        } else {
+            self.summary[summary] |= 1 << bit;
+        }
+    }
+
+    /// Recomputes the summary and the count from the words.
+    fn rebuild(&mut self) {
+        self.summary.clear();
+        self.summary.resize(self.words.len().div_ceil(BITS), 0);
+        for word in 0..self.words.len() {
+            self.update_summary(word);
+        }
+        self.count = self.words.iter().map(|w| w.count_ones() as usize).sum();
+    }
+}
+
+/// An iterator over the indices of the occupied slots in an [`Occupancy`].
+#[derive(Debug, Clone)]
+pub(crate) struct OccupiedBits<'a> {
+    occupancy: &'a Occupancy,
+    /// The index of the current summary word, and its remaining set bits.
+    summary: usize,
+    summary_bits: u64,
+    /// The index of the current word, and its remaining set bits.
+    word: usize,
+    word_bits: u64,
+}
+
+impl Iterator for OccupiedBits<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.word_bits == 0 {
+            while self.summary_bits == 0 {
+                self.summary += 1;
+                self.summary_bits = *self.occupancy.summary.get(self.summary)?;
+            }
+            let bit = self.summary_bits.trailing_zeros() as usize;
+            self.summary_bits &= self.summary_bits - 1;
+            self.word = self.summary * BITS + bit;
+            self.word_bits = self.occupancy.words[self.word];
+        }
+        let bit = self.word_bits.trailing_zeros() as usize;
+        self.word_bits &= self.word_bits - 1;
+        Some(self.word * BITS + bit)
+    }
+}
+
+/// An iterator over the indices of the holes in an [`Occupancy`].
+#[derive(Debug, Clone)]
+pub(crate) struct HoleBits<'a> {
+    occupancy: &'a Occupancy,
+    /// The index of the current word, and its remaining holes as set bits.
+    word: usize,
+    word_bits: u64,
+}
+
+impl Iterator for HoleBits<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.word_bits == 0 {
+            self.word += 1;
+            if self.word >= self.occupancy.words.len() {
+                return None;
+            }
+            self.word_bits = self.occupancy.hole_bits(self.word);
+        }
+        let bit = self.word_bits.trailing_zeros() as usize;
+        self.word_bits &= self.word_bits - 1;
+        Some(self.word * BITS + bit)
+    }
+}
+
+/// An iterator over the indices of the occupied slots of an [`ExpandVec`](crate::ExpandVec).
+///
+/// Created by [`ExpandVec::iter_occupied`](crate::ExpandVec::iter_occupied).
+#[derive(Debug, Clone)]
+pub struct IterOccupied<'a> {
+    inner: IterOccupiedInner<'a>,
+}
+
+#[derive(Debug, Clone)]
+enum IterOccupiedInner<'a> {
+    /// Occupancy is not tracked, so every slot counts as occupied.
+    All(Range<usize>),
+    Tracked(OccupiedBits<'a>),
+}
+
+impl<'a> IterOccupied<'a> {
+    pub(crate) fn new(occupancy: Option<&'a Occupancy>, len: usize) -> Self {
+        let inner = match occupancy {
+            Some(occupancy) => IterOccupiedInner::Tracked(occupancy.iter_occupied()),
+            None => IterOccupiedInner::All(0..len),
+        };
+        Self { inner }
+    }
+}
+
+impl Iterator for IterOccupied<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        match &mut self.inner {
+            IterOccupiedInner::All(range) => range.next(),
+            IterOccupiedInner::Tracked(bits) => bits.next(),
+        }
+    }
+}
+
+/// An iterator over the indices of the holes of an [`ExpandVec`](crate::ExpandVec).
+///
+/// Created by [`ExpandVec::iter_holes`](crate::ExpandVec::iter_holes).
+#[derive(Debug, Clone)]
+pub struct IterHoles<'a> {
+    inner: Option<HoleBits<'a>>,
+}
+
+impl<'a> IterHoles<'a> {
+    pub(crate) fn new(occupancy: Option<&'a Occupancy>) -> Self {
+        Self {
+            inner: occupancy.map(Occupancy::iter_holes),
+        }
+    }
+}
+
+impl Iterator for IterHoles<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        self.inner.as_mut()?.next()
+    }
+}
@@ -0,0 +1,252 @@
+//! A collection that expands like an [`ExpandVec`](crate::ExpandVec), but stores its items as
+//! runs of equal values.
+use std::iter;
+use std::ops::{Range, RangeBounds};
+
+use crate::{expand_range, DefaultFill, ExpandError, Fill, FillValue};
+
+/// A run of items with the same value, that ends before `end` and starts at the end of the
+/// previous run.
+#[derive(Debug, Clone)]
+struct Run<T> {
+    end: usize,
+    value: T,
+}
+
+/// A collection that expands to provide a mutable reference to items beyond the stored
+/// collection, storing its items run-length encoded.
+///
+/// Piecewise-constant data, such as a state per timestep, is stored as one value per run rather
+/// than one value per item. Writing through [`set`](ExpandRuns::set) or
+/// [`fill_range`](ExpandRuns::fill_range) splits the runs it overwrites and merges the written
+/// run with neighbouring runs of the same value. Gaps created by expansion form a single run of
+/// the fill value.
+#[derive(Debug, Clone)]
+pub struct ExpandRuns<T, S = DefaultFill> {
+    /// The runs in index order. No run is empty.
+    runs: Vec<Run<T>>,
+    fill: S,
+}
+
+impl<T> ExpandRuns<T> {
+    pub fn new() -> Self {
+        Self::with_filler(DefaultFill)
+    }
+}
+
+impl<T: Clone> ExpandRuns<T, FillValue<T>> {
+    /// Creates an empty collection that fills any gaps created by expansion with clones of
+    /// `value`, rather than with [`Default::default`].
+    pub fn with_fill(value: T) -> Self {
+        Self::with_filler(FillValue(value))
+    }
+}
+
+impl<T, S> ExpandRuns<T, S> {
+    fn with_filler(fill: S) -> Self {
+        Self {
+            runs: Vec::new(),
+            fill,
+        }
+    }
+
+    /// Returns the filler that creates new items when the collection expands.
+    pub fn filler(&self) -> &S {
+        &self.fill
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.runs.last().map_or(0, |run| run.end)
+    }
+
+    /// Returns `true` if the collection contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Returns the number of runs that store the elements.
+    pub fn run_count(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Returns the position of the run that holds `index`, or the number of runs if `index` is out
+    /// of bounds.
+    fn run_position(&self, index: usize) -> usize {
+        self.runs.partition_point(|run| run.end <= index)
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.runs
+            .get(self.run_position(index))
+            .map(|run| &run.value)
+    }
+
+    /// Returns an iterator over the runs, as the range of indices they cover and their value.
+    pub fn iter_runs(&self) -> impl Iterator<Item = (Range<usize>, &T)> + '_ {
+        self.runs.iter().scan(0, |start, run| {
+            let range = *start..run.end;
+            *start = run.end;
+            Some((range, &run.value))
+        })
+    }
+
+    /// Returns an iterator over all elements in index order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.iter_runs()
+            .flat_map(|(range, value)| iter::repeat_n(value, range.len()))
+    }
+}
+
+impl<T: Clone + PartialEq, S: Fill<T>> ExpandRuns<T, S> {
+    /// Expands the collection with fill values to `len` elements, if it is shorter.
+    fn expand_to(&mut self, len: usize) {
+        if len <= self.len() {
+            return;
+        }
+        let gap = self.fill.fill();
+        match self.runs.last_mut() {
+            Some(last) if last.value == gap => last.end = len,
+            _ => self.runs.push(Run {
+                end: len,
+                value: gap,
+            }),
+        }
+    }
+
+    /// Splits the run that holds `index`, which must not be beyond the length, so that a run
+    /// starts at `index`. Returns the position of that run.
+    fn split_at(&mut self, index: usize) -> usize {
+        let position = self.run_position(index);
+        let start = position
+            .checked_sub(1)
+            .map_or(0, |prev| self.runs[prev].end);
+        if position < self.runs.len() && start < index {
+            let value = self.runs[position].value.clone();
+            self.runs.insert(position, Run { end: index, value });
+            position + 1
+        } else {
+            position
+        }
+    }
+
+    /// Merges the run at `position` with its neighbours, if they hold the same value.
+    fn merge_around(&mut self, position: usize) {
+        if self
+            .runs
+            .get(position + 1)
+            .is_some_and(|next| next.value == self.runs[position].value)
+        {
+            self.runs.remove(position);
+        }
+        if position > 0 && self.runs[position - 1].value == self.runs[position].value {
+            self.runs.remove(position - 1);
+        }
+    }
+
+    /// Appends an element to the back of a collection, merging it into the last run if it holds
+    /// the same value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length is already `usize::MAX`.
+    pub fn push(&mut self, value: T) {
+        let end = self
+            .len()
+            .checked_add(1)
+            .unwrap_or_else(|| panic!("{}", ExpandError::IndexOverflow));
+        match self.runs.last_mut() {
+            Some(last) if last.value == value => last.end = end,
+            _ => self.runs.push(Run { end, value }),
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index`, or `None` if it is out of bounds.
+    ///
+    /// Like [`expand_get_mut`](ExpandRuns::expand_get_mut), this splits the element off into a
+    /// run of its own.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len() {
+            return None;
+        }
+        let position = self.split_at(index);
+        self.split_at(index + 1);
+        Some(&mut self.runs[position].value)
+    }
+
+    /// Always returns a mutable reference to an element.
+    /// If the index points beyond the length of the collection, it is expanded with fill values
+    /// to fit the index.
+    ///
+    /// The element is split off into a run of its own, because the reference allows it to take
+    /// any value. Use [`merge_runs`](ExpandRuns::merge_runs) to merge it back afterwards, or
+    /// prefer [`set`](ExpandRuns::set), which merges as it writes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is `usize::MAX`.
+    pub fn expand_get_mut(&mut self, index: usize) -> &mut T {
+        let end = index
+            .checked_add(1)
+            .unwrap_or_else(|| panic!("{}", ExpandError::IndexOverflow));
+        self.expand_to(end);
+        // We can safely unwrap since the collection was expanded to fit the index.
+        self.get_mut(index).unwrap()
+    }
+
+    /// Stores `value` at `index`, expanding if necessary, and returns the previous value if the
+    /// slot already existed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is `usize::MAX`.
+    pub fn set(&mut self, index: usize, value: T) -> Option<T> {
+        let previous = self.get(index).cloned();
+        let end = index
+            .checked_add(1)
+            .unwrap_or_else(|| panic!("{}", ExpandError::IndexOverflow));
+        self.fill_range(index..end, value);
+        previous
+    }
+
+    /// Stores `value` at every index in `range`, expanding with fill values to fit the end of the
+    /// range if necessary. The range becomes a single run, merged with neighbouring runs of the
+    /// same value.
+    ///
+    /// Both exclusive (`a..b`, `..b`) and inclusive (`a..=b`, `..=b`) ranges are accepted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the end of the range is unbounded (`a..` or `..`), if the start of the range is
+    /// greater than its end, or if an inclusive end is `usize::MAX`.
+    pub fn fill_range<R: RangeBounds<usize>>(&mut self, range: R, value: T) {
+        let Range { start, end } = expand_range(range);
+        self.expand_to(end);
+        if start == end {
+            return;
+        }
+        let first = self.split_at(start);
+        let last = self.split_at(end);
+        self.runs.splice(first..last, [Run { end, value }]);
+        self.merge_around(first);
+    }
+
+    /// Merges all neighbouring runs that hold the same value, such as the ones left behind by
+    /// [`expand_get_mut`](ExpandRuns::expand_get_mut).
+    pub fn merge_runs(&mut self) {
+        self.runs.dedup_by(|next, run| {
+            let same = next.value == run.value;
+            if same {
+                run.end = next.end;
+            }
+            same
+        })
+    }
+}
+
+impl<T, S: Default> Default for ExpandRuns<T, S> {
+    fn default() -> Self {
+        Self::with_filler(S::default())
+    }
+}
@@ -0,0 +1,155 @@
+//! [Serde](::serde) support, enabled by the `serde` feature.
+//!
+//! By default, an [`ExpandVec`] is (de)serialized in the dense form, as a sequence of all of its
+//! items, just like a [`Vec`]. The [`sparse`] module provides a sparse form that omits the items
+//! that are equal to the fill value.
+//!
+//! Only the items are serialized. Settings such as the [`max_len`](ExpandVec::max_len), the
+//! auto-trim mode and occupancy tracking are not, and a deserialized collection starts out with
+//! their defaults.
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::ExpandVec;
+
+impl<T: Serialize, S> Serialize for ExpandVec<T, S> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        self.inner.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>, S: Default> Deserialize<'de> for ExpandVec<T, S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(Self::from)
+    }
+}
+
+/// (De)serialization of an [`ExpandVec`] in the sparse form, for use with
+/// `#[serde(with = "expand_vec::serde::sparse")]`.
+///
+/// The sparse form is a struct with the `len` of the collection, its `fill` value, and a map of
+/// `items` from index to value, which omits every item that is equal to the fill value.
+/// Deserializing it restores a collection of exactly `len` items, with the omitted ones filled in
+/// with clones of the stored fill value, and a filler created from it by [`FromFillValue`](sparse::FromFillValue).
+///
+/// ```
+/// # use expand_vec::{ExpandVec, FillValue};
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Samples {
+///     #[serde(with = "expand_vec::serde::sparse")]
+///     values: ExpandVec<u32>,
+///     #[serde(with = "expand_vec::serde::sparse")]
+///     sentinels: ExpandVec<u32, FillValue<u32>>,
+/// }
+/// ```
+pub mod sparse {
+    use std::collections::BTreeMap;
+
+    use ::serde::de::Error;
+    use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::{DefaultFill, ExpandVec, Fill, FillValue};
+
+    /// A filler that can be recreated from the fill value stored in the sparse form.
+    pub trait FromFillValue<T>: Fill<T> {
+        /// Creates the filler for a collection whose fill value was `value`.
+        fn from_fill_value(value: T) -> Self;
+    }
+
+    impl<T: Default> FromFillValue<T> for DefaultFill {
+        fn from_fill_value(_: T) -> Self {
+            DefaultFill
+        }
+    }
+
+    impl<T: Clone> FromFillValue<T> for FillValue<T> {
+        fn from_fill_value(value: T) -> Self {
+            FillValue(value)
+        }
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename = "ExpandVec")]
+    struct SparseRef<'a, T: Serialize + PartialEq> {
+        len: usize,
+        fill: &'a T,
+        items: SparseItems<'a, T>,
+    }
+
+    /// The items that differ from `fill`, serialized as a map from index to value.
+    struct SparseItems<'a, T> {
+        items: &'a [T],
+        fill: &'a T,
+    }
+
+    impl<T: Serialize + PartialEq> Serialize for SparseItems<'_, T> {
+        fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+            let items = self.items.iter().enumerate();
+            serializer.collect_map(items.filter(|(_, item)| *item != self.fill))
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "ExpandVec")]
+    struct Sparse<T> {
+        len: usize,
+        fill: T,
+        items: BTreeMap<usize, T>,
+    }
+
+    /// Serializes `vec` in the sparse form.
+    pub fn serialize<T, S, Ser>(
+        vec: &ExpandVec<T, S>,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error>
+    where
+        T: Serialize + PartialEq,
+        S: Fill<T>,
+        Ser: Serializer,
+    {
+        let fill = vec.fill.fill();
+        SparseRef {
+            len: vec.inner.len(),
+            fill: &fill,
+            items: SparseItems {
+                items: &vec.inner,
+                fill: &fill,
+            },
+        }
+        .serialize(serializer)
+    }
+
+    /// Deserializes a collection from the sparse form.
+    ///
+    /// Returns an error if an index in the `items` is not below the `len`, or if the memory for
+    /// `len` items cannot be allocated.
+    pub fn deserialize<'de, T, S, D>(deserializer: D) -> Result<ExpandVec<T, S>, D::Error>
+    where
+        T: Deserialize<'de> + Clone,
+        S: FromFillValue<T>,
+        D: Deserializer<'de>,
+    {
+        let Sparse { len, fill, items } = Sparse::<T>::deserialize(deserializer)?;
+        if let Some((&index, _)) = items.last_key_value() {
+            if index >= len {
+                return Err(D::Error::custom(format_args!(
+                    "item index {index} is out of bounds for a length of {len}"
+                )));
+            }
+        }
+        // The length comes from the input, so a failure to allocate it is an error, not a panic.
+        let mut inner = Vec::new();
+        inner
+            .try_reserve_exact(len)
+            .map_err(|err| D::Error::custom(format_args!("cannot allocate {len} items: {err}")))?;
+        let mut items = items.into_iter().peekable();
+        inner.extend(
+            (0..len).map(|index| match items.next_if(|(i, _)| *i == index) {
+                Some((_, item)) => item,
+                None => fill.clone(),
+            }),
+        );
+        let mut vec = ExpandVec::with_filler(S::from_fill_value(fill));
+        vec.inner = inner;
+        Ok(vec)
+    }
+}
@@ -0,0 +1,209 @@
+//! Tests for the compact binary format of [`ExpandVec`].
+use expand_vec::binary::{BinaryElement, DecodeError, VERSION};
+use expand_vec::ExpandVec;
+use proptest::prelude::*;
+
+fn encode<T: BinaryElement>(ev: &ExpandVec<T>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    ev.write_to(&mut bytes).unwrap();
+    bytes
+}
+
+/// Replaces the checksum so that corrupted contents get past the checksum verification.
+fn reseal(bytes: &mut Vec<u8>) {
+    bytes.truncate(bytes.len() - 4);
+    let mut crc = !0u32;
+    for &byte in bytes.iter() {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                crc >> 1 ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    bytes.extend_from_slice(&(!crc).to_le_bytes());
+}
+
+/// Returns the header up to, but not including, the length.
+fn header(tag: u8, endianness: u8) -> Vec<u8> {
+    let mut bytes = b"EXVC".to_vec();
+    bytes.extend_from_slice(&[VERSION, tag, endianness]);
+    bytes
+}
+
+#[test]
+fn layout_of_a_small_collection() {
+    let mut ev = ExpandVec::<u16>::new();
+    *ev.expand_get_mut(3) = 0x0102;
+    ev.expand_get_mut(9);
+    let bytes = encode(&ev);
+    #[rustfmt::skip]
+    let expected = [
+        b'E', b'X', b'V', b'C', VERSION, u16::TAG, b'L', 10,
+        3, 1, 0x02, 0x01,
+        6, 0,
+    ];
+    assert_eq!(bytes[..bytes.len() - 4], expected);
+}
+
+#[test]
+fn long_gaps_are_compact() {
+    let mut ev = ExpandVec::<u64>::new();
+    *ev.expand_get_mut(1_000_000) = 1;
+    let bytes = encode(&ev);
+    assert!(bytes.len() < 32);
+    let back = ExpandVec::<u64>::read_from(&bytes[..]).unwrap();
+    assert_eq!(back[..], ev[..]);
+}
+
+#[test]
+fn empty_round_trips() {
+    let ev = ExpandVec::<i32>::new();
+    let back = ExpandVec::<i32>::read_from(&encode(&ev)[..]).unwrap();
+    assert!(back.is_empty());
+}
+
+#[test]
+fn negative_zero_is_preserved() {
+    let ev = ExpandVec::from(vec![0.0f64, -0.0, f64::NAN, 0.0]);
+    let back = ExpandVec::<f64>::read_from(&encode(&ev)[..]).unwrap();
+    let bits = |ev: &ExpandVec<f64>| ev.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+    assert_eq!(bits(&back), bits(&ev));
+}
+
+#[test]
+fn big_endian_items_are_read() {
+    let mut bytes = header(u32::TAG, b'B');
+    bytes.extend_from_slice(&[2, 1, 1]);
+    bytes.extend_from_slice(&0x0a0b0c0du32.to_be_bytes());
+    bytes.extend_from_slice(&[0; 4]);
+    reseal(&mut bytes);
+    let back = ExpandVec::<u32>::read_from(&bytes[..]).unwrap();
+    assert_eq!(back[..], [0, 0x0a0b0c0d]);
+}
+
+#[test]
+fn type_mismatch() {
+    let bytes = encode(&ExpandVec::from(vec![1u32, 2]));
+    assert!(matches!(
+        ExpandVec::<i32>::read_from(&bytes[..]),
+        Err(DecodeError::TypeMismatch { expected, found }) if expected == i32::TAG && found == u32::TAG
+    ));
+}
+
+#[test]
+fn flipped_bit_is_a_checksum_mismatch() {
+    let mut bytes = encode(&ExpandVec::from(vec![0u8, 0, 7, 8, 0]));
+    bytes[9] ^= 0x10;
+    assert!(matches!(
+        ExpandVec::<u8>::read_from(&bytes[..]),
+        Err(DecodeError::ChecksumMismatch { .. })
+    ));
+}
+
+#[test]
+fn header_errors() {
+    let bytes = encode(&ExpandVec::from(vec![1u8]));
+
+    let mut bad_magic = bytes.clone();
+    bad_magic[0] = b'X';
+    assert!(matches!(
+        ExpandVec::<u8>::read_from(&bad_magic[..]),
+        Err(DecodeError::BadMagic)
+    ));
+
+    let mut bad_version = bytes.clone();
+    bad_version[4] = VERSION + 1;
+    reseal(&mut bad_version);
+    assert!(matches!(
+        ExpandVec::<u8>::read_from(&bad_version[..]),
+        Err(DecodeError::UnsupportedVersion(v)) if v == VERSION + 1
+    ));
+
+    let mut bad_endianness = bytes;
+    bad_endianness[6] = b'X';
+    reseal(&mut bad_endianness);
+    assert!(matches!(
+        ExpandVec::<u8>::read_from(&bad_endianness[..]),
+        Err(DecodeError::BadEndianness(b'X'))
+    ));
+}
+
+#[test]
+fn inconsistent_records_are_corrupt() {
+    // A record that claims more items than the length.
+    let mut bytes = header(u8::TAG, b'L');
+    bytes.extend_from_slice(&[2, 5, 0]);
+    bytes.extend_from_slice(&[0; 4]);
+    reseal(&mut bytes);
+    assert!(matches!(
+        ExpandVec::<u8>::read_from(&bytes[..]),
+        Err(DecodeError::Corrupt(_))
+    ));
+
+    // An empty record, which would never make progress.
+    let mut bytes = header(u8::TAG, b'L');
+    bytes.extend_from_slice(&[2, 0, 0]);
+    bytes.extend_from_slice(&[0; 4]);
+    reseal(&mut bytes);
+    assert!(matches!(
+        ExpandVec::<u8>::read_from(&bytes[..]),
+        Err(DecodeError::Corrupt(_))
+    ));
+
+    // A huge length with too few data bytes.
+    let mut bytes = header(u64::TAG, b'L');
+    bytes.extend_from_slice(&[0xff, 0xff, 0x7f, 0, 0xff, 0xff, 0x7f, 1]);
+    bytes.extend_from_slice(&[0; 4]);
+    reseal(&mut bytes);
+    assert!(matches!(
+        ExpandVec::<u64>::read_from(&bytes[..]),
+        Err(DecodeError::Truncated)
+    ));
+}
+
+#[test]
+fn short_input_is_truncated() {
+    assert!(matches!(
+        ExpandVec::<u8>::read_from(&[0u8, 1][..]),
+        Err(DecodeError::Truncated)
+    ));
+}
+
+proptest! {
+    #[test]
+    fn round_trip(items in prop::collection::vec(prop_oneof![Just(0i16), any::<i16>()], 0..200)) {
+        let ev = ExpandVec::from(items);
+        let back = ExpandVec::<i16>::read_from(&encode(&ev)[..]).unwrap();
+        prop_assert_eq!(&back[..], &ev[..]);
+    }
+
+    #[test]
+    fn corruption_never_panics(
+        items in prop::collection::vec(prop_oneof![Just(0u32), any::<u32>()], 0..50),
+        flips in prop::collection::vec((any::<prop::sample::Index>(), 1..=255u8), 1..4),
+        resealed in any::<bool>(),
+    ) {
+        let mut bytes = encode(&ExpandVec::from(items));
+        for (index, mask) in flips {
+            let i = index.index(bytes.len());
+            bytes[i] ^= mask;
+        }
+        if resealed {
+            reseal(&mut bytes);
+        }
+        let _ = ExpandVec::<u32>::read_from(&bytes[..]);
+    }
+
+    #[test]
+    fn truncation_never_panics(
+        items in prop::collection::vec(any::<u8>(), 0..50),
+        cut in any::<prop::sample::Index>(),
+    ) {
+        let bytes = encode(&ExpandVec::from(items));
+        let cut = cut.index(bytes.len());
+        prop_assert!(ExpandVec::<u8>::read_from(&bytes[..cut]).is_err());
+    }
+}
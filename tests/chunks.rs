@@ -0,0 +1,55 @@
+//! Model-based tests that check [`ExpandChunks`] against a plain [`Vec`].
+use expand_vec::ExpandChunks;
+use proptest::prelude::*;
+
+mod common;
+
+use common::{apply, op};
+
+proptest! {
+    #[test]
+    fn matches_vec_model(page_size in 1..16usize, ops in prop::collection::vec(op(any::<u8>()), 0..128)) {
+        let mut chunks = ExpandChunks::with_page_size(page_size);
+        let mut model = Vec::new();
+        for op in ops {
+            apply(&mut chunks, &mut model, op)?;
+        }
+        prop_assert_eq!(chunks.len(), model.len());
+        prop_assert!(chunks.iter().eq(&model));
+    }
+}
+
+#[test]
+fn middle_pages_stay_unallocated() {
+    let mut chunks = ExpandChunks::with_page_size(8);
+    *chunks.expand_get_mut(3) = 1u32;
+    *chunks.expand_get_mut(100) = 2;
+    assert_eq!(chunks.len(), 101);
+    assert_eq!(chunks.allocated_pages(), 2);
+    assert_eq!(chunks.get(50), Some(&0));
+    assert_eq!(chunks.allocated_pages(), 2);
+    *chunks.get_mut(50).unwrap() = 3;
+    assert_eq!(chunks.allocated_pages(), 3);
+}
+
+#[test]
+fn with_fill_reads_gaps_as_fill() {
+    let mut chunks = ExpandChunks::with_fill(4, u32::MAX);
+    *chunks.expand_get_mut(9) = 1;
+    assert_eq!(chunks.get(0), Some(&u32::MAX));
+    assert_eq!(chunks.get(8), Some(&u32::MAX));
+    assert_eq!(chunks.get(9), Some(&1));
+}
+
+#[test]
+#[should_panic(expected = "page size")]
+fn zero_page_size_panics() {
+    ExpandChunks::<u8>::with_page_size(0);
+}
+
+#[test]
+#[should_panic(expected = "overflows a usize")]
+fn expand_get_mut_at_usize_max_panics() {
+    let mut chunks = ExpandChunks::<u8>::with_page_size(1);
+    chunks.expand_get_mut(usize::MAX);
+}
@@ -0,0 +1,92 @@
+//! The model shared by the tests of the sibling collections, which all provide the same basic
+//! expanding surface as [`ExpandVec`](expand_vec::ExpandVec).
+use expand_vec::{ExpandChunks, ExpandMap, ExpandRuns};
+use proptest::prelude::*;
+
+/// Upper bound for generated indices, so that expansion stays cheap.
+pub const MAX_INDEX: usize = 64;
+
+/// The basic expanding surface of a sibling collection.
+pub trait Expand {
+    fn push(&mut self, value: u8);
+    fn get(&self, index: usize) -> Option<&u8>;
+    fn get_mut(&mut self, index: usize) -> Option<&mut u8>;
+    fn expand_get_mut(&mut self, index: usize) -> &mut u8;
+}
+
+macro_rules! impl_expand {
+    ($($ty:ty),*) => {
+        $(
+            impl Expand for $ty {
+                fn push(&mut self, value: u8) {
+                    <$ty>::push(self, value)
+                }
+
+                fn get(&self, index: usize) -> Option<&u8> {
+                    <$ty>::get(self, index)
+                }
+
+                fn get_mut(&mut self, index: usize) -> Option<&mut u8> {
+                    <$ty>::get_mut(self, index)
+                }
+
+                fn expand_get_mut(&mut self, index: usize) -> &mut u8 {
+                    <$ty>::expand_get_mut(self, index)
+                }
+            }
+        )*
+    };
+}
+
+impl_expand!(ExpandChunks<u8>, ExpandMap<u8>, ExpandRuns<u8>);
+
+#[derive(Debug, Clone)]
+pub enum Op {
+    Push(u8),
+    Get(usize),
+    GetMut(usize, u8),
+    ExpandGetMut(usize, u8),
+}
+
+/// Generates operations that write values drawn from `value`.
+pub fn op(value: impl Strategy<Value = u8> + Clone) -> impl Strategy<Value = Op> {
+    prop_oneof![
+        value.clone().prop_map(Op::Push),
+        (0..MAX_INDEX).prop_map(Op::Get),
+        (0..MAX_INDEX, value.clone()).prop_map(|(i, v)| Op::GetMut(i, v)),
+        (0..MAX_INDEX, value).prop_map(|(i, v)| Op::ExpandGetMut(i, v)),
+    ]
+}
+
+/// Applies `op` to both `collection` and `model`.
+pub fn apply(
+    collection: &mut impl Expand,
+    model: &mut Vec<u8>,
+    op: Op,
+) -> Result<(), TestCaseError> {
+    match op {
+        Op::Push(v) => {
+            collection.push(v);
+            model.push(v);
+        }
+        Op::Get(i) => prop_assert_eq!(collection.get(i), model.get(i)),
+        Op::GetMut(i, v) => {
+            let (l, r) = (collection.get_mut(i), model.get_mut(i));
+            prop_assert_eq!(l.is_some(), r.is_some());
+            if let (Some(l), Some(r)) = (l, r) {
+                *l = v;
+                *r = v;
+            }
+        }
+        Op::ExpandGetMut(i, v) => {
+            if i >= model.len() {
+                model.resize(i + 1, 0);
+            }
+            let (l, r) = (collection.expand_get_mut(i), &mut model[i]);
+            prop_assert_eq!(*l, *r);
+            *l = v;
+            *r = v;
+        }
+    }
+    Ok(())
+}
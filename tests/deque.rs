@@ -0,0 +1,68 @@
+//! Model-based tests that check [`ExpandDeque`] against a plain [`BTreeMap`].
+use std::collections::BTreeMap;
+
+use expand_vec::ExpandDeque;
+use proptest::prelude::*;
+
+/// Bound for generated indices, so that expansion stays cheap.
+const MAX_INDEX: isize = 32;
+
+proptest! {
+    #[test]
+    fn matches_model(writes in prop::collection::vec((-MAX_INDEX..MAX_INDEX, any::<u8>()), 0..64)) {
+        let mut deque = ExpandDeque::new();
+        let mut model = BTreeMap::new();
+        for (i, v) in writes {
+            if let (Some(&min), Some(&max)) = (model.keys().next(), model.keys().next_back()) {
+                for j in i.min(min)..=i.max(max) {
+                    model.entry(j).or_insert(0);
+                }
+            }
+            let (l, r) = (deque.expand_get_mut(i), model.entry(i).or_insert(0));
+            prop_assert_eq!(*l, *r);
+            *l = v;
+            *r = v;
+        }
+        prop_assert_eq!(deque.len(), model.len());
+        prop_assert_eq!(deque.min_index(), model.keys().next().copied());
+        prop_assert_eq!(deque.max_index(), model.keys().next_back().copied());
+        for i in -MAX_INDEX - 1..=MAX_INDEX {
+            prop_assert_eq!(deque.get(i), model.get(&i));
+        }
+        prop_assert!(deque.iter().eq(model.iter().map(|(&i, v)| (i, v))));
+        prop_assert!(deque.iter().rev().eq(model.iter().rev().map(|(&i, v)| (i, v))));
+    }
+}
+
+#[test]
+fn grows_at_both_ends() {
+    let mut deque = ExpandDeque::with_fill('.');
+    *deque.expand_get_mut(2) = 'b';
+    *deque.expand_get_mut(-2) = 'a';
+    assert_eq!(deque.min_index(), Some(-2));
+    assert_eq!(deque.max_index(), Some(2));
+    let (offset, inner) = deque.raw_parts();
+    assert_eq!(offset, -2);
+    assert_eq!(inner, ['a', '.', '.', '.', 'b']);
+}
+
+#[test]
+fn empty_has_no_indices() {
+    let deque = ExpandDeque::<u8>::new();
+    assert_eq!(deque.min_index(), None);
+    assert_eq!(deque.max_index(), None);
+    assert_eq!(deque.get(0), None);
+}
+
+#[test]
+fn overflowing_span_panics_before_changing_the_deque() {
+    let mut deque = ExpandDeque::new();
+    *deque.expand_get_mut(isize::MIN) = 1u8;
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        deque.expand_get_mut(isize::MAX);
+    }));
+    assert!(result.is_err());
+    assert_eq!(deque.len(), 1);
+    assert_eq!(deque.max_index(), Some(isize::MIN));
+    assert!(deque.iter().eq([(isize::MIN, &1)]));
+}
@@ -0,0 +1,46 @@
+//! Tests for the entry API of [`ExpandVec`].
+use expand_vec::{Entry, ExpandVec};
+
+#[test]
+fn peeking_does_not_grow() {
+    let mut ev = ExpandVec::<u8>::new();
+    assert!(matches!(ev.entry(5), Entry::Vacant(_)));
+    assert_eq!(ev.entry(5).and_modify(|v| *v += 1).key(), 5);
+    assert!(ev.is_empty());
+}
+
+#[test]
+fn vacant_insert_grows() {
+    let mut ev = ExpandVec::with_fill(9u8);
+    assert_eq!(*ev.entry(2).or_insert(1), 1);
+    assert_eq!(*ev.entry(2).or_insert(5), 1);
+    *ev.entry(2).and_modify(|v| *v += 1).or_default() += 10;
+    assert_eq!(*ev.entry(3).or_insert_with_key(|i| i as u8), 3);
+    assert_eq!(ev[..], [9, 9, 12, 3]);
+}
+
+#[test]
+fn occupied_entry() {
+    let mut ev = ExpandVec::<u8>::from([1, 2]);
+    match ev.entry(1) {
+        Entry::Occupied(mut entry) => {
+            assert_eq!(*entry.get(), 2);
+            assert_eq!(entry.insert(3), 2);
+            *entry.into_mut() += 1;
+        }
+        Entry::Vacant(_) => unreachable!(),
+    }
+    assert_eq!(ev[..], [1, 4]);
+}
+
+#[test]
+fn holes_are_vacant_when_tracked() {
+    let mut ev = ExpandVec::<u8>::new();
+    ev.set_track_occupancy(true);
+    *ev.expand_get_mut(2) = 7;
+    assert!(matches!(ev.entry(0), Entry::Vacant(_)));
+    assert!(matches!(ev.entry(2), Entry::Occupied(_)));
+    *ev.entry(0).or_insert_with(|| 5) += 1;
+    assert!(ev.is_occupied(0));
+    assert_eq!(ev[..], [6, 0, 7]);
+}
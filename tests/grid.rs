@@ -0,0 +1,104 @@
+//! Model-based tests that check [`ExpandGrid`] against rows of plain [`Vec`]s.
+use expand_vec::{ExpandGrid, Layout};
+use proptest::prelude::*;
+
+/// Upper bound for generated rows and columns, so that expansion stays cheap.
+const MAX_INDEX: usize = 16;
+
+/// The reference behaviour of `expand_get_mut`, expressed in terms of rows of [`Vec`]s.
+fn model_expand_get_mut(
+    model: &mut Vec<Vec<u8>>,
+    layout: Layout,
+    (row, col): (usize, usize),
+) -> &mut u8 {
+    if row >= model.len() {
+        let width = match layout {
+            Layout::Dense => model.first().map_or(0, Vec::len),
+            Layout::Ragged => 0,
+        };
+        model.resize(row + 1, vec![0; width]);
+    }
+    if col >= model[row].len() {
+        match layout {
+            Layout::Dense => model.iter_mut().for_each(|r| r.resize(col + 1, 0)),
+            Layout::Ragged => model[row].resize(col + 1, 0),
+        }
+    }
+    &mut model[row][col]
+}
+
+fn check_layout(layout: Layout, writes: Vec<((usize, usize), u8)>) -> Result<(), TestCaseError> {
+    let mut grid = match layout {
+        Layout::Dense => ExpandGrid::new(),
+        Layout::Ragged => ExpandGrid::ragged(),
+    };
+    let mut model = Vec::new();
+    for (cell, v) in writes {
+        let (l, r) = (
+            grid.expand_get_mut(cell),
+            model_expand_get_mut(&mut model, layout, cell),
+        );
+        prop_assert_eq!(*l, *r);
+        *l = v;
+        *r = v;
+    }
+
+    prop_assert_eq!(grid.layout(), layout);
+    prop_assert_eq!(grid.height(), model.len());
+    prop_assert_eq!(grid.width(), model.iter().map(Vec::len).max().unwrap_or(0));
+    for row in 0..=MAX_INDEX {
+        for col in 0..=MAX_INDEX {
+            prop_assert_eq!(
+                grid.get((row, col)),
+                model.get(row).and_then(|r| r.get(col))
+            );
+        }
+    }
+    prop_assert!(grid.rows().eq(model.iter().map(Vec::as_slice)));
+    for (col, column) in grid.columns().enumerate() {
+        prop_assert!(column.eq(model.iter().map(|r| r.get(col))));
+    }
+    prop_assert_eq!(grid.raw_rows(), model);
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn dense_matches_model(writes in prop::collection::vec(((0..MAX_INDEX, 0..MAX_INDEX), any::<u8>()), 0..32)) {
+        check_layout(Layout::Dense, writes)?;
+    }
+
+    #[test]
+    fn ragged_matches_model(writes in prop::collection::vec(((0..MAX_INDEX, 0..MAX_INDEX), any::<u8>()), 0..32)) {
+        check_layout(Layout::Ragged, writes)?;
+    }
+}
+
+#[test]
+fn with_fill() {
+    let mut grid = ExpandGrid::with_fill(Layout::Dense, '.');
+    *grid.expand_get_mut((1, 2)) = '#';
+    *grid.expand_get_mut((0, 0)) = '@';
+    assert_eq!(
+        grid.raw_rows(),
+        vec![vec!['@', '.', '.'], vec!['.', '.', '#']]
+    );
+}
+
+#[test]
+fn overflowing_cells_panic_before_changing_the_grid() {
+    for (layout, cell) in [
+        (Layout::Dense, (1, 1 << (usize::BITS - 1))),
+        (Layout::Dense, (usize::MAX, 0)),
+        (Layout::Ragged, (0, usize::MAX)),
+    ] {
+        let mut grid = ExpandGrid::with_fill(layout, 0u8);
+        *grid.expand_get_mut((0, 1)) = 1;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            grid.expand_get_mut(cell);
+        }));
+        assert!(result.is_err());
+        assert_eq!((grid.height(), grid.width()), (1, 2));
+        assert_eq!(grid.raw_rows(), vec![vec![0, 1]]);
+    }
+}
@@ -0,0 +1,65 @@
+//! Model-based tests that check [`ExpandMap`] against a plain [`Vec`].
+use expand_vec::{ExpandMap, ExpandVec};
+use proptest::prelude::*;
+
+mod common;
+
+use common::{apply, op};
+
+proptest! {
+    #[test]
+    fn matches_vec_model(ops in prop::collection::vec(op(any::<u8>()), 0..128)) {
+        let mut map = ExpandMap::new();
+        let mut model = Vec::new();
+        for op in ops {
+            apply(&mut map, &mut model, op)?;
+        }
+        prop_assert_eq!(map.len(), model.len());
+        prop_assert!(map.iter().eq(&model));
+        prop_assert!(map.iter_stored().all(|(&i, v)| model[i] == *v));
+
+        let vec = ExpandVec::from(map.clone());
+        prop_assert_eq!(vec.clone().raw_vec(), model.clone());
+        let back = ExpandMap::from(vec);
+        prop_assert_eq!(back.len(), model.len());
+        prop_assert!(back.iter_stored().all(|(_, &v)| v != 0));
+        prop_assert!(back.iter().eq(&model));
+    }
+}
+
+#[test]
+fn huge_index_stores_one_slot() {
+    let mut map = ExpandMap::new();
+    *map.expand_get_mut(4_000_000_000) = 1u64;
+    assert_eq!(map.len(), 4_000_000_001);
+    assert_eq!(map.stored_len(), 1);
+    assert_eq!(map.get(123), Some(&0));
+    assert_eq!(map.get(4_000_000_000), Some(&1));
+    assert_eq!(map.get(4_000_000_001), None);
+}
+
+#[test]
+fn with_fill_reads_gaps_as_fill() {
+    let mut map = ExpandMap::with_fill(f64::NAN);
+    *map.expand_get_mut(2) = 1.0;
+    assert!(map.get(0).unwrap().is_nan());
+    assert_eq!(map.stored_len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "overflows a usize")]
+fn expand_get_mut_at_usize_max_panics() {
+    let mut map = ExpandMap::<u8>::new();
+    map.expand_get_mut(usize::MAX);
+}
+
+#[test]
+fn push_at_full_length_panics_before_changing_the_map() {
+    let mut map = ExpandMap::new();
+    *map.expand_get_mut(usize::MAX - 1) = 1u8;
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| map.push(2)));
+    assert!(result.is_err());
+    assert_eq!(map.len(), usize::MAX);
+    assert_eq!(map.stored_len(), 1);
+    assert_eq!(map.get(usize::MAX - 1), Some(&1));
+}
@@ -0,0 +1,626 @@
+//! Model-based tests that check every public method of [`ExpandVec`] against a plain [`Vec`].
+use expand_vec::{ExpandError, ExpandVec, Fill};
+use proptest::prelude::*;
+
+/// Upper bound for generated indices, so that expansion stays cheap.
+const MAX_INDEX: usize = 64;
+
+#[derive(Debug, Clone)]
+enum Op {
+    Push(u8),
+    Get(usize),
+    GetOrDefault(usize),
+    GetRange(usize, usize),
+    GetMut(usize, u8),
+    GetRangeMut(usize, usize, u8),
+    ExpandGetMut(usize, u8),
+    ExpandGetMutWith(usize, u8),
+    ExpandGetRangeMut(usize, usize, u8),
+    ExpandGetRangeInclusiveMut(usize, usize, u8),
+    ExpandToWith(usize),
+    ExpandTo(usize),
+    Fill(usize, usize, u8),
+    FillWith(usize, usize),
+    CopyFromSliceAt(usize, Vec<u8>),
+    Index(usize),
+    IndexRangeMut(usize, usize, u8),
+    AutoIndexMut(usize, u8),
+    Iter,
+    IterMut(u8),
+    Extend(Vec<u8>),
+    Len,
+    LogicalLen,
+    TrimTrailingDefaults,
+    Set(usize, u8),
+    Replace(usize, u8),
+    Pop,
+    Insert(usize, u8),
+    Remove(usize),
+    SwapRemove(usize),
+    Truncate(usize),
+    Clear,
+    Resize(usize, u8),
+    ResizeWith(usize),
+    Reserve(usize),
+    ShrinkToFit,
+    Clone,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        any::<u8>().prop_map(Op::Push),
+        (0..MAX_INDEX).prop_map(Op::Get),
+        (0..MAX_INDEX).prop_map(Op::GetOrDefault),
+        (0..MAX_INDEX, 0..MAX_INDEX).prop_map(|(a, b)| Op::GetRange(a, b)),
+        (0..MAX_INDEX, any::<u8>()).prop_map(|(i, v)| Op::GetMut(i, v)),
+        (0..MAX_INDEX, 0..MAX_INDEX, any::<u8>()).prop_map(|(a, b, v)| Op::GetRangeMut(a, b, v)),
+        (0..MAX_INDEX, any::<u8>()).prop_map(|(i, v)| Op::ExpandGetMut(i, v)),
+        (0..MAX_INDEX, any::<u8>()).prop_map(|(i, v)| Op::ExpandGetMutWith(i, v)),
+        (0..MAX_INDEX).prop_map(Op::ExpandToWith),
+        (0..MAX_INDEX).prop_map(Op::ExpandTo),
+        (0..MAX_INDEX, 0..MAX_INDEX, any::<u8>()).prop_map(|(a, b, v)| Op::Fill(
+            a.min(b),
+            a.max(b),
+            v
+        )),
+        (0..MAX_INDEX, 0..MAX_INDEX).prop_map(|(a, b)| Op::FillWith(a.min(b), a.max(b))),
+        (0..MAX_INDEX, prop::collection::vec(any::<u8>(), 0..8))
+            .prop_map(|(offset, src)| Op::CopyFromSliceAt(offset, src)),
+        (0..MAX_INDEX, 0..MAX_INDEX, any::<u8>()).prop_map(|(a, b, v)| Op::ExpandGetRangeMut(
+            a.min(b),
+            a.max(b),
+            v
+        )),
+        (0..MAX_INDEX, 0..MAX_INDEX, any::<u8>())
+            .prop_map(|(a, b, v)| Op::ExpandGetRangeInclusiveMut(a.min(b), a.max(b), v)),
+        (0..MAX_INDEX).prop_map(Op::Index),
+        (0..MAX_INDEX, 0..MAX_INDEX, any::<u8>()).prop_map(|(a, b, v)| Op::IndexRangeMut(
+            a.min(b),
+            a.max(b),
+            v
+        )),
+        (0..MAX_INDEX, any::<u8>()).prop_map(|(i, v)| Op::AutoIndexMut(i, v)),
+        Just(Op::Iter),
+        any::<u8>().prop_map(Op::IterMut),
+        prop::collection::vec(any::<u8>(), 0..8).prop_map(Op::Extend),
+        Just(Op::Len),
+        Just(Op::LogicalLen),
+        Just(Op::TrimTrailingDefaults),
+        (0..MAX_INDEX, any::<u8>()).prop_map(|(i, v)| Op::Set(i, v)),
+        (0..MAX_INDEX, any::<u8>()).prop_map(|(i, v)| Op::Replace(i, v)),
+        Just(Op::Pop),
+        (0..MAX_INDEX, any::<u8>()).prop_map(|(i, v)| Op::Insert(i, v)),
+        (0..MAX_INDEX).prop_map(Op::Remove),
+        (0..MAX_INDEX).prop_map(Op::SwapRemove),
+        (0..MAX_INDEX).prop_map(Op::Truncate),
+        Just(Op::Clear),
+        (0..MAX_INDEX, any::<u8>()).prop_map(|(n, v)| Op::Resize(n, v)),
+        (0..MAX_INDEX).prop_map(Op::ResizeWith),
+        (0..MAX_INDEX).prop_map(Op::Reserve),
+        Just(Op::ShrinkToFit),
+        Just(Op::Clone),
+    ]
+}
+
+/// The reference behaviour of `expand_get_mut`, expressed in terms of a [`Vec`].
+fn model_expand_get_mut(model: &mut Vec<u8>, filler: u8, index: usize) -> &mut u8 {
+    if index >= model.len() {
+        model.resize(index + 1, filler);
+    }
+    &mut model[index]
+}
+
+/// The filler used for the closure-based expansion methods.
+fn fill(index: usize) -> u8 {
+    (index as u8).wrapping_mul(3)
+}
+
+/// The reference behaviour of `expand_to_with`, expressed in terms of a [`Vec`].
+fn model_expand_to_with(model: &mut Vec<u8>, len: usize) {
+    while model.len() < len {
+        model.push(fill(model.len()));
+    }
+}
+
+/// Applies `op` to both `ev` and `model`, where `filler` is the value `ev` fills gaps with.
+fn apply<S: Fill<u8> + Clone>(
+    ev: &mut ExpandVec<u8, S>,
+    model: &mut Vec<u8>,
+    filler: u8,
+    op: Op,
+) -> Result<(), TestCaseError> {
+    match op {
+        Op::Push(v) => {
+            ev.push(v);
+            model.push(v);
+        }
+        Op::Get(i) => prop_assert_eq!(ev.get(i), model.get(i)),
+        Op::GetOrDefault(i) => {
+            prop_assert_eq!(ev.get_or_default(i), model.get(i).unwrap_or(&filler))
+        }
+        Op::GetRange(a, b) => prop_assert_eq!(ev.get(a..b), model.get(a..b)),
+        Op::GetMut(i, v) => {
+            let (l, r) = (ev.get_mut(i), model.get_mut(i));
+            prop_assert_eq!(l.is_some(), r.is_some());
+            if let (Some(l), Some(r)) = (l, r) {
+                *l = v;
+                *r = v;
+            }
+        }
+        Op::GetRangeMut(a, b, v) => {
+            let (l, r) = (ev.get_mut(a..b), model.get_mut(a..b));
+            prop_assert_eq!(l.is_some(), r.is_some());
+            if let (Some(l), Some(r)) = (l, r) {
+                l.fill(v);
+                r.fill(v);
+            }
+        }
+        Op::ExpandGetMut(i, v) => {
+            let l = ev.expand_get_mut(i);
+            let r = model_expand_get_mut(model, filler, i);
+            prop_assert_eq!(*l, *r);
+            *l = v;
+            *r = v;
+        }
+        Op::ExpandGetMutWith(i, v) => {
+            let l = ev.expand_get_mut_with(i, fill);
+            model_expand_to_with(model, i + 1);
+            let r = &mut model[i];
+            prop_assert_eq!(*l, *r);
+            *l = v;
+            *r = v;
+        }
+        Op::ExpandGetRangeMut(a, b, v) => {
+            if b > model.len() {
+                model.resize(b, filler);
+            }
+            let (l, r) = (ev.expand_get_range_mut(a..b), &mut model[a..b]);
+            prop_assert_eq!(&*l, &*r);
+            l.fill(v);
+            r.fill(v);
+        }
+        Op::ExpandGetRangeInclusiveMut(a, b, v) => {
+            if b >= model.len() {
+                model.resize(b + 1, filler);
+            }
+            let (l, r) = (ev.expand_get_range_mut(a..=b), &mut model[a..=b]);
+            prop_assert_eq!(&*l, &*r);
+            l.fill(v);
+            r.fill(v);
+        }
+        Op::ExpandToWith(len) => {
+            ev.expand_to_with(len, fill);
+            model_expand_to_with(model, len);
+        }
+        Op::ExpandTo(len) => {
+            ev.expand_to(len);
+            if len > model.len() {
+                model.resize(len, filler);
+            }
+        }
+        Op::Fill(a, b, v) => {
+            ev.fill(a..b, v);
+            if b > model.len() {
+                model.resize(b, filler);
+            }
+            model[a..b].fill(v);
+        }
+        Op::FillWith(a, b) => {
+            ev.fill_with(a..b, fill);
+            if b > model.len() {
+                model.resize(b, filler);
+            }
+            for (i, item) in model[a..b].iter_mut().enumerate() {
+                *item = fill(a + i);
+            }
+        }
+        Op::CopyFromSliceAt(offset, src) => {
+            ev.copy_from_slice_at(offset, &src);
+            let end = offset + src.len();
+            if end > model.len() {
+                model.resize(end, filler);
+            }
+            model[offset..end].copy_from_slice(&src);
+        }
+        Op::Index(i) => {
+            if i < model.len() {
+                prop_assert_eq!(ev[i], model[i]);
+            }
+        }
+        Op::IndexRangeMut(a, b, v) => {
+            if b <= model.len() {
+                ev[a..b].fill(v);
+                model[a..b].fill(v);
+            }
+        }
+        Op::AutoIndexMut(i, v) => {
+            ev.auto()[i] = v;
+            *model_expand_get_mut(model, filler, i) = v;
+        }
+        Op::Iter => {
+            prop_assert!(ev.iter().eq(model.iter()));
+            prop_assert!((&*ev).into_iter().rev().eq(model.iter().rev()));
+        }
+        Op::IterMut(v) => {
+            ev.iter_mut().step_by(2).for_each(|x| *x ^= v);
+            model.iter_mut().step_by(2).for_each(|x| *x ^= v);
+            for x in &mut *ev {
+                *x = x.wrapping_add(1);
+            }
+            for x in &mut *model {
+                *x = x.wrapping_add(1);
+            }
+        }
+        Op::Extend(items) => {
+            ev.extend(items.iter());
+            model.extend(items.iter());
+            ev.extend(items.clone());
+            model.extend(items);
+        }
+        Op::Len => {
+            prop_assert_eq!(ev.len(), model.len());
+            prop_assert_eq!(ev.is_empty(), model.is_empty());
+            prop_assert!(ev.capacity() >= ev.len());
+        }
+        Op::Set(i, v) => {
+            let old = model.get(i).copied();
+            *model_expand_get_mut(model, filler, i) = v;
+            prop_assert_eq!(ev.set(i, v), old);
+        }
+        Op::Replace(i, v) => {
+            let old = model.get(i).copied();
+            *model_expand_get_mut(model, filler, i) = v;
+            prop_assert_eq!(ev.replace(i, v), old);
+        }
+        Op::LogicalLen => {
+            let len = model
+                .iter()
+                .rposition(|&x| x != filler)
+                .map_or(0, |i| i + 1);
+            prop_assert_eq!(ev.logical_len(), len);
+        }
+        Op::TrimTrailingDefaults => {
+            ev.trim_trailing_defaults();
+            while model.last() == Some(&filler) {
+                model.pop();
+            }
+        }
+        Op::Pop => prop_assert_eq!(ev.pop(), model.pop()),
+        Op::Insert(i, v) => {
+            if i <= model.len() {
+                ev.insert(i, v);
+                model.insert(i, v);
+            }
+        }
+        Op::Remove(i) => {
+            if i < model.len() {
+                prop_assert_eq!(ev.remove(i), model.remove(i));
+            }
+        }
+        Op::SwapRemove(i) => {
+            if i < model.len() {
+                prop_assert_eq!(ev.swap_remove(i), model.swap_remove(i));
+            }
+        }
+        Op::Truncate(n) => {
+            ev.truncate(n);
+            model.truncate(n);
+        }
+        Op::Clear => {
+            ev.clear();
+            model.clear();
+        }
+        Op::Resize(n, v) => {
+            ev.resize(n, v);
+            model.resize(n, v);
+        }
+        Op::ResizeWith(n) => {
+            ev.resize_with(n, || 9);
+            model.resize_with(n, || 9);
+        }
+        Op::Reserve(n) => {
+            ev.reserve(n);
+            prop_assert!(ev.capacity() >= ev.len() + n);
+            ev.reserve_exact(n);
+            prop_assert!(ev.capacity() >= ev.len() + n);
+        }
+        Op::ShrinkToFit => {
+            ev.shrink_to_fit();
+            prop_assert!(ev.capacity() >= ev.len());
+        }
+        Op::Clone => *ev = ev.clone(),
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn matches_vec_model(ops in prop::collection::vec(op(), 0..128)) {
+        let mut ev = ExpandVec::new();
+        let mut model = Vec::new();
+        for op in ops {
+            apply(&mut ev, &mut model, 0, op)?;
+        }
+        prop_assert_eq!(ev.into_iter().collect::<Vec<_>>(), model);
+    }
+
+    #[test]
+    fn collects_like_vec(items in prop::collection::vec(any::<u8>(), 0..64)) {
+        let ev: ExpandVec<u8> = items.iter().copied().collect();
+        prop_assert_eq!(ev.raw_vec(), items);
+    }
+
+    #[test]
+    fn matches_vec_model_with_fill(filler: u8, ops in prop::collection::vec(op(), 0..128)) {
+        let mut ev = ExpandVec::with_fill(filler);
+        let mut model = Vec::new();
+        for op in ops {
+            apply(&mut ev, &mut model, filler, op)?;
+        }
+        prop_assert_eq!(ev.raw_vec(), model);
+    }
+
+    #[test]
+    fn expand_get_mut_fits_index_exactly(init in prop::collection::vec(any::<u8>(), 0..MAX_INDEX), index in 0..2 * MAX_INDEX) {
+        let mut ev = ExpandVec::new();
+        for &v in &init {
+            ev.push(v);
+        }
+        *ev.expand_get_mut(index) = 42;
+        let inner = ev.raw_vec();
+        prop_assert_eq!(inner.len(), init.len().max(index + 1));
+        prop_assert_eq!(inner[index], 42);
+        for (i, (&got, &orig)) in inner.iter().zip(&init).enumerate() {
+            if i != index {
+                prop_assert_eq!(got, orig);
+            }
+        }
+        prop_assert!(inner[init.len()..].iter().enumerate().all(|(j, &v)| init.len() + j == index || v == 0));
+    }
+}
+
+#[test]
+fn new_and_default_are_empty() {
+    assert!(ExpandVec::<u8>::new().raw_vec().is_empty());
+    assert!(ExpandVec::<u8>::default().raw_vec().is_empty());
+}
+
+#[test]
+fn expand_get_mut_at_len() {
+    let mut ev = ExpandVec::new();
+    ev.push(1);
+    *ev.expand_get_mut(1) = 2;
+    assert_eq!(ev.raw_vec(), vec![1, 2]);
+}
+
+#[test]
+fn expand_with_needs_neither_default_nor_clone() {
+    #[derive(Debug, PartialEq)]
+    struct Id(usize);
+
+    let mut ev = ExpandVec::new();
+    ev.expand_get_mut_with(2, Id).0 += 10;
+    ev.expand_to_with(4, Id);
+    ev.expand_to_with(1, |_| unreachable!());
+    assert_eq!(ev.raw_vec(), vec![Id(0), Id(1), Id(12), Id(3)]);
+}
+
+#[test]
+fn expand_get_mut_without_clone() {
+    use std::sync::Mutex;
+
+    let mut ev: ExpandVec<Mutex<u8>> = ExpandVec::new();
+    *ev.expand_get_mut(2).get_mut().unwrap() = 7;
+    let inner: Vec<u8> = ev
+        .raw_vec()
+        .into_iter()
+        .map(|m| m.into_inner().unwrap())
+        .collect();
+    assert_eq!(inner, vec![0, 0, 7]);
+}
+
+#[test]
+#[should_panic(expected = "unbounded end")]
+fn expand_get_range_mut_rejects_unbounded_end() {
+    ExpandVec::<u8>::new().expand_get_range_mut(3..);
+}
+
+#[test]
+fn with_fill_needs_no_default() {
+    #[derive(Debug, Clone, PartialEq)]
+    struct Sentinel(u32);
+
+    let mut ev = ExpandVec::with_fill(Sentinel(u32::MAX));
+    *ev.expand_get_mut(2) = Sentinel(5);
+    ev.expand_get_range_mut(3..=3)[0].0 = 6;
+    assert_eq!(ev.filler().0, Sentinel(u32::MAX));
+    assert_eq!(
+        ev.raw_vec(),
+        vec![
+            Sentinel(u32::MAX),
+            Sentinel(u32::MAX),
+            Sentinel(5),
+            Sentinel(6)
+        ]
+    );
+}
+
+#[test]
+fn try_expand_get_mut_respects_max_len() {
+    let mut ev = ExpandVec::new();
+    ev.set_max_len(Some(4));
+    assert_eq!(ev.max_len(), Some(4));
+    *ev.try_expand_get_mut(3).unwrap() = 1u8;
+    assert_eq!(
+        ev.try_expand_get_mut(4),
+        Err(ExpandError::OverLimit { len: 5, max_len: 4 })
+    );
+    ev.set_max_len(None);
+    assert_eq!(
+        ev.try_expand_get_mut(usize::MAX),
+        Err(ExpandError::IndexOverflow)
+    );
+    assert_eq!(ev.raw_vec(), vec![0, 0, 0, 1]);
+}
+
+#[test]
+fn try_expand_get_mut_reports_allocation_failure() {
+    let mut ev = ExpandVec::<u64>::new();
+    let err = ev.try_expand_get_mut(usize::MAX / 2).unwrap_err();
+    assert!(matches!(err, ExpandError::AllocationFailure(_)));
+    assert!(ev.raw_vec().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "maximum length of 2")]
+fn expand_get_mut_panics_over_max_len() {
+    let mut ev = ExpandVec::<u8>::new();
+    ev.set_max_len(Some(2));
+    ev.expand_get_mut(2);
+}
+
+#[test]
+fn bulk_writes_within_the_stored_items_ignore_max_len() {
+    let mut ev = ExpandVec::<u8>::from([1, 2, 3, 4]);
+    ev.set_max_len(Some(2));
+    ev.fill(0..3, 7);
+    ev.fill_with(3..4, |i| i as u8);
+    ev.copy_from_slice_at(1, &[8, 9]);
+    assert_eq!(ev[..], [7, 8, 9, 3]);
+}
+
+#[test]
+fn bulk_writes_auto_trim() {
+    let mut ev = ExpandVec::<u8>::from([1, 2, 3]);
+    ev.set_auto_trim(true);
+    ev.fill(1..5, 0);
+    assert_eq!(ev[..], [1]);
+    ev.fill_with(2..4, |i| i as u8 % 3);
+    assert_eq!(ev[..], [1, 0, 2]);
+    ev.copy_from_slice_at(1, &[4, 0, 0]);
+    assert_eq!(ev[..], [1, 4]);
+}
+
+#[test]
+fn bulk_writes_over_max_len_leave_the_collection_unaltered() {
+    let mut ev = ExpandVec::<u8>::from([1, 2]);
+    ev.set_max_len(Some(3));
+    ev.fill(0..3, 5);
+    assert_eq!(ev[..], [5, 5, 5]);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        ev.copy_from_slice_at(1, &[7, 8, 9])
+    }));
+    assert!(result.is_err());
+    assert_eq!(ev[..], [5, 5, 5]);
+}
+
+#[test]
+fn try_push_and_try_reserve() {
+    let mut ev = ExpandVec::<u64>::new();
+    ev.try_push(1).unwrap();
+    ev.try_reserve(16).unwrap();
+    assert!(ev.try_reserve(usize::MAX).is_err());
+    ev.try_push(2).unwrap();
+    assert_eq!(ev.raw_vec(), vec![1, 2]);
+}
+
+#[test]
+#[should_panic]
+fn index_out_of_bounds_panics() {
+    let mut ev = ExpandVec::<u8>::new();
+    ev.auto()[1] = 1;
+    let _ = ev[2];
+}
+
+#[test]
+fn slice_conversions() {
+    fn sum(items: &[u8]) -> u8 {
+        items.iter().sum()
+    }
+
+    let mut ev = ExpandVec::<u8>::from([1, 2, 3]);
+    assert_eq!(sum(&ev), 6);
+    assert_eq!(ev.len(), 3);
+    ev.sort_by(|a, b| b.cmp(a));
+    ev.as_mut()[0] = 4;
+    assert_eq!(ev.as_ref(), [4, 2, 1]);
+    assert_eq!(std::borrow::Borrow::<[u8]>::borrow(&ev), [4, 2, 1]);
+
+    let ev = ExpandVec::<u8>::from(&[5, 6][..]);
+    assert_eq!(Box::<[u8]>::from(ev), vec![5, 6].into_boxed_slice());
+    let ev = ExpandVec::<u8>::from(vec![7]);
+    assert_eq!(Vec::from(ev), vec![7]);
+}
+
+#[test]
+fn with_capacity() {
+    let ev = ExpandVec::<u8>::with_capacity(10);
+    assert!(ev.capacity() >= 10);
+    assert!(ev.is_empty());
+}
+
+#[test]
+fn auto_trim() {
+    let mut ev = ExpandVec::<u8>::from([1, 0, 2, 0, 0]);
+    assert_eq!(ev.logical_len(), 3);
+    ev.set_auto_trim(true);
+    assert!(ev.is_auto_trim());
+    assert_eq!(ev[..], [1, 0, 2]);
+    assert_eq!(ev.set(2, 0), Some(2));
+    assert_eq!(ev[..], [1]);
+    assert_eq!(ev.set(5, 0), None);
+    assert_eq!(ev[..], [1]);
+    ev.set_auto_trim(false);
+    ev.extend([0, 5]);
+    ev.set_auto_trim(true);
+    ev.truncate(2);
+    assert_eq!(ev[..], [1]);
+    ev.set_auto_trim(false);
+    assert_eq!(ev.set(3, 0), None);
+    assert_eq!(ev[..], [1, 0, 0, 0]);
+}
+
+#[test]
+fn expand_get_many_mut() {
+    use std::slice::GetDisjointMutError;
+
+    let mut ev = ExpandVec::<u8>::from([1, 2]);
+    let [a, b] = ev.expand_get_many_mut([1, 4]).unwrap();
+    std::mem::swap(a, b);
+    assert_eq!(ev[..], [1, 0, 0, 0, 2]);
+    assert_eq!(
+        ev.expand_get_many_mut([7, 0, 7]),
+        Err(GetDisjointMutError::OverlappingIndices)
+    );
+    assert_eq!(ev.len(), 5);
+    let [] = ev.expand_get_many_mut([]).unwrap();
+}
+
+#[test]
+fn get_or_default_borrows_the_fill_value() {
+    let ev = ExpandVec::with_fill(String::from("gap"));
+    assert!(std::ptr::eq(ev.get_or_default(3), &ev.filler().0));
+
+    let ev = ExpandVec::<String>::new();
+    assert!(std::ptr::eq(ev.get_or_default(3), ev.get_or_default(7)));
+    assert_eq!(ev.get_or_default(3), "");
+}
+
+#[test]
+fn opt_in_settings_are_boxed() {
+    // Only the inner `Vec`, a pointer to the settings, and the lazily created fill value.
+    assert!(std::mem::size_of::<ExpandVec<u8>>() <= 2 * std::mem::size_of::<Vec<u8>>());
+    let mut ev = ExpandVec::<u8>::new();
+    ev.set_max_len(Some(4));
+    ev.set_auto_trim(true);
+    ev.set_track_occupancy(true);
+    *ev.expand_get_mut(2) = 1;
+    assert_eq!(ev.max_len(), Some(4));
+    assert!(ev.is_auto_trim() && ev.is_tracking_occupancy());
+    ev.set_max_len(None);
+    ev.set_auto_trim(false);
+    ev.set_track_occupancy(false);
+    assert_eq!(ev.max_len(), None);
+    assert!(!ev.is_auto_trim() && !ev.is_tracking_occupancy());
+    assert_eq!(ev[..], [0, 0, 1]);
+}
@@ -0,0 +1,197 @@
+//! Model-based tests that check the occupancy tracking of [`ExpandVec`] against a plain [`Vec`]
+//! of flags.
+use expand_vec::ExpandVec;
+use proptest::prelude::*;
+
+/// Upper bound for generated indices, large enough to span several bitmap words.
+const MAX_INDEX: usize = 300;
+
+#[derive(Debug, Clone)]
+enum Op {
+    Push,
+    Pop,
+    ExpandGetMut(usize),
+    ExpandGetMutWith(usize),
+    ExpandToWith(usize),
+    ExpandGetRangeMut(usize, usize),
+    ExpandTo(usize),
+    Fill(usize, usize),
+    CopyFromSliceAt(usize, usize),
+    Set(usize),
+    Insert(usize),
+    Remove(usize),
+    SwapRemove(usize),
+    Truncate(usize),
+    Resize(usize),
+    Extend(usize),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        Just(Op::Push),
+        Just(Op::Pop),
+        (0..MAX_INDEX).prop_map(Op::ExpandGetMut),
+        (0..MAX_INDEX).prop_map(Op::ExpandGetMutWith),
+        (0..MAX_INDEX).prop_map(Op::ExpandToWith),
+        (0..MAX_INDEX, 0..8usize).prop_map(|(a, n)| Op::ExpandGetRangeMut(a, a + n)),
+        (0..MAX_INDEX).prop_map(Op::ExpandTo),
+        (0..MAX_INDEX, 0..8usize).prop_map(|(a, n)| Op::Fill(a, a + n)),
+        (0..MAX_INDEX, 0..8usize).prop_map(|(a, n)| Op::CopyFromSliceAt(a, n)),
+        (0..MAX_INDEX).prop_map(Op::Set),
+        (0..MAX_INDEX).prop_map(Op::Insert),
+        (0..MAX_INDEX).prop_map(Op::Remove),
+        (0..MAX_INDEX).prop_map(Op::SwapRemove),
+        (0..MAX_INDEX).prop_map(Op::Truncate),
+        (0..MAX_INDEX).prop_map(Op::Resize),
+        (0..8usize).prop_map(Op::Extend),
+    ]
+}
+
+/// Grows the model to `len` holes, if it is shorter.
+fn grow(model: &mut Vec<bool>, len: usize) {
+    if len > model.len() {
+        model.resize(len, false);
+    }
+}
+
+proptest! {
+    #[test]
+    fn matches_flag_model(initial in 0..8usize, ops in prop::collection::vec(op(), 0..64)) {
+        let mut ev = ExpandVec::<u8>::new();
+        ev.resize(initial, 1);
+        ev.set_track_occupancy(true);
+        let mut model = vec![true; initial];
+        for op in ops {
+            match op {
+                Op::Push => {
+                    ev.push(1);
+                    model.push(true);
+                }
+                Op::Pop => {
+                    ev.pop();
+                    model.pop();
+                }
+                Op::ExpandGetMut(i) => {
+                    ev.expand_get_mut(i);
+                    grow(&mut model, i + 1);
+                    model[i] = true;
+                }
+                Op::ExpandGetMutWith(i) => {
+                    ev.expand_get_mut_with(i, |_| 0);
+                    grow(&mut model, i + 1);
+                    model[i] = true;
+                }
+                Op::ExpandToWith(len) => {
+                    ev.expand_to_with(len, |_| 0);
+                    grow(&mut model, len);
+                }
+                Op::ExpandGetRangeMut(a, b) => {
+                    ev.expand_get_range_mut(a..b);
+                    grow(&mut model, b);
+                    model[a..b].fill(true);
+                }
+                Op::ExpandTo(len) => {
+                    ev.expand_to(len);
+                    grow(&mut model, len);
+                }
+                Op::Fill(a, b) => {
+                    ev.fill(a..b, 1);
+                    grow(&mut model, b);
+                    model[a..b].fill(true);
+                }
+                Op::CopyFromSliceAt(offset, n) => {
+                    ev.copy_from_slice_at(offset, &vec![1; n]);
+                    grow(&mut model, offset + n);
+                    model[offset..offset + n].fill(true);
+                }
+                Op::Set(i) => {
+                    ev.set(i, 1);
+                    grow(&mut model, i + 1);
+                    model[i] = true;
+                }
+                Op::Insert(i) => {
+                    if i <= model.len() {
+                        ev.insert(i, 1);
+                        model.insert(i, true);
+                    }
+                }
+                Op::Remove(i) => {
+                    if i < model.len() {
+                        ev.remove(i);
+                        model.remove(i);
+                    }
+                }
+                Op::SwapRemove(i) => {
+                    if i < model.len() {
+                        ev.swap_remove(i);
+                        model.swap_remove(i);
+                    }
+                }
+                Op::Truncate(len) => {
+                    ev.truncate(len);
+                    model.truncate(len);
+                }
+                Op::Resize(len) => {
+                    ev.resize(len, 1);
+                    model.resize(len, true);
+                }
+                Op::Extend(n) => {
+                    ev.extend(vec![1; n]);
+                    model.extend(vec![true; n]);
+                }
+            }
+            prop_assert_eq!(ev.len(), model.len());
+        }
+        for i in 0..MAX_INDEX + 16 {
+            prop_assert_eq!(ev.is_occupied(i), model.get(i) == Some(&true));
+        }
+        prop_assert_eq!(ev.occupied_count(), model.iter().filter(|&&o| o).count());
+        prop_assert!(ev.iter_occupied().eq((0..model.len()).filter(|&i| model[i])));
+        prop_assert!(ev.iter_holes().eq((0..model.len()).filter(|&i| !model[i])));
+    }
+}
+
+#[test]
+fn sparse_occupancy() {
+    let mut ev = ExpandVec::<u8>::new();
+    ev.set_track_occupancy(true);
+    for i in [5, 4_095, 4_096, 70_000, 1_000_000] {
+        *ev.expand_get_mut(i) = 1;
+    }
+    assert_eq!(
+        ev.iter_occupied().collect::<Vec<_>>(),
+        [5, 4_095, 4_096, 70_000, 1_000_000]
+    );
+    assert_eq!(ev.occupied_count(), 5);
+    assert_eq!(ev.iter_holes().count(), ev.len() - 5);
+}
+
+#[test]
+fn untracked_counts_everything_as_occupied() {
+    let mut ev = ExpandVec::<u8>::new();
+    ev.expand_get_mut(3);
+    assert!(!ev.is_tracking_occupancy());
+    assert!(ev.is_occupied(0));
+    assert_eq!(ev.occupied_count(), 4);
+    assert!(ev.iter_occupied().eq(0..4));
+    assert_eq!(ev.iter_holes().next(), None);
+}
+
+#[test]
+fn auto_trim_keeps_occupied_fill_values() {
+    let mut ev = ExpandVec::<u8>::new();
+    ev.set_track_occupancy(true);
+    ev.set_auto_trim(true);
+    ev.set(3, 0);
+    assert_eq!(ev.len(), 4);
+    assert_eq!(ev.occupied_count(), 1);
+
+    // Removing the last slot leaves trailing holes, which are trimmed.
+    ev.set(6, 1);
+    ev.pop();
+    assert_eq!(ev.len(), 4);
+    assert!(ev.iter_occupied().eq([3]));
+
+    ev.trim_trailing_defaults();
+    assert!(ev.is_empty());
+}
@@ -0,0 +1,139 @@
+//! Model-based tests that check [`ExpandRuns`] against a plain [`Vec`].
+use expand_vec::ExpandRuns;
+use proptest::prelude::*;
+
+mod common;
+
+use common::{apply, MAX_INDEX};
+
+#[derive(Debug, Clone)]
+enum Op {
+    Common(common::Op),
+    Set(usize, u8),
+    FillRange(usize, usize, u8),
+    MergeRuns,
+}
+
+/// Values are drawn from a small set, so that runs merge often.
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        common::op(0..3u8).prop_map(Op::Common),
+        (0..MAX_INDEX, 0..3u8).prop_map(|(i, v)| Op::Set(i, v)),
+        (0..MAX_INDEX, 0..MAX_INDEX, 0..3u8).prop_map(|(a, b, v)| Op::FillRange(
+            a.min(b),
+            a.max(b),
+            v
+        )),
+        Just(Op::MergeRuns),
+    ]
+}
+
+/// Checks that the runs cover the model exactly, and returns whether neighbouring runs are all
+/// distinct.
+fn check_runs(runs: &ExpandRuns<u8>, model: &[u8]) -> Result<bool, TestCaseError> {
+    let mut end = 0;
+    let mut previous = None;
+    let mut merged = true;
+    for (range, value) in runs.iter_runs() {
+        prop_assert_eq!(range.start, end);
+        prop_assert!(!range.is_empty());
+        prop_assert!(model[range.clone()].iter().all(|v| v == value));
+        merged &= previous != Some(value);
+        previous = Some(value);
+        end = range.end;
+    }
+    prop_assert_eq!(end, model.len());
+    Ok(merged)
+}
+
+proptest! {
+    #[test]
+    fn matches_vec_model(ops in prop::collection::vec(op(), 0..128)) {
+        let mut runs = ExpandRuns::new();
+        let mut model = Vec::new();
+        let mut merged = true;
+        for op in ops {
+            match op {
+                Op::Common(op) => {
+                    // A mutable reference splits the element off into a run of its own.
+                    if matches!(op, common::Op::GetMut(..) | common::Op::ExpandGetMut(..)) {
+                        merged = false;
+                    }
+                    apply(&mut runs, &mut model, op)?;
+                }
+                Op::Set(i, v) => {
+                    let previous = model.get(i).copied();
+                    if i >= model.len() {
+                        model.resize(i + 1, 0);
+                    }
+                    model[i] = v;
+                    prop_assert_eq!(runs.set(i, v), previous);
+                }
+                Op::FillRange(start, end, v) => {
+                    if end > model.len() {
+                        model.resize(end, 0);
+                    }
+                    model[start..end].fill(v);
+                    runs.fill_range(start..end, v);
+                }
+                Op::MergeRuns => {
+                    runs.merge_runs();
+                    merged = true;
+                }
+            }
+            let distinct = check_runs(&runs, &model)?;
+            if merged {
+                prop_assert!(distinct);
+            }
+        }
+        prop_assert_eq!(runs.len(), model.len());
+        prop_assert!(runs.iter().eq(&model));
+    }
+}
+
+#[test]
+fn writes_split_and_merge_runs() {
+    let mut runs = ExpandRuns::new();
+    runs.fill_range(2..8, 1);
+    assert_eq!(
+        runs.iter_runs().collect::<Vec<_>>(),
+        [(0..2, &0), (2..8, &1)]
+    );
+    runs.set(4, 2);
+    assert_eq!(
+        runs.iter_runs().collect::<Vec<_>>(),
+        [(0..2, &0), (2..4, &1), (4..5, &2), (5..8, &1)]
+    );
+    runs.set(4, 1);
+    assert_eq!(
+        runs.iter_runs().collect::<Vec<_>>(),
+        [(0..2, &0), (2..8, &1)]
+    );
+    runs.fill_range(..=9, 0);
+    assert_eq!(runs.iter_runs().collect::<Vec<_>>(), [(0..10, &0)]);
+}
+
+#[test]
+fn gaps_use_the_fill_value() {
+    let mut runs = ExpandRuns::with_fill(7);
+    runs.set(3, 1);
+    runs.fill_range(6..6, 1);
+    assert_eq!(
+        runs.iter_runs().collect::<Vec<_>>(),
+        [(0..3, &7), (3..4, &1), (4..6, &7)]
+    );
+    assert_eq!(runs.run_count(), 3);
+}
+
+#[test]
+fn push_at_full_length_panics_before_changing_the_runs() {
+    let mut runs = ExpandRuns::new();
+    *runs.expand_get_mut(usize::MAX - 1) = 1u8;
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| runs.push(2)));
+    assert!(result.is_err());
+    assert_eq!(runs.len(), usize::MAX);
+    assert_eq!(
+        runs.iter_runs().collect::<Vec<_>>(),
+        [(0..usize::MAX - 1, &0), (usize::MAX - 1..usize::MAX, &1)]
+    );
+}
@@ -0,0 +1,76 @@
+//! Tests for the dense and sparse serde representations of [`ExpandVec`].
+#![cfg(feature = "serde")]
+use expand_vec::{ExpandVec, FillValue};
+use proptest::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Sparse {
+    #[serde(with = "expand_vec::serde::sparse")]
+    values: ExpandVec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SparseSentinels {
+    #[serde(with = "expand_vec::serde::sparse")]
+    values: ExpandVec<u32, FillValue<u32>>,
+}
+
+#[test]
+fn dense_is_a_sequence() {
+    let mut ev = ExpandVec::<u32>::new();
+    *ev.expand_get_mut(2) = 5;
+    let json = serde_json::to_string(&ev).unwrap();
+    assert_eq!(json, "[0,0,5]");
+    let back: ExpandVec<u32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back[..], ev[..]);
+}
+
+#[test]
+fn sparse_omits_defaults_and_keeps_length() {
+    let mut values = ExpandVec::new();
+    *values.expand_get_mut(1) = 7;
+    values.expand_get_mut(4);
+    let json = serde_json::to_string(&Sparse { values }).unwrap();
+    assert_eq!(json, r#"{"values":{"len":5,"fill":0,"items":{"1":7}}}"#);
+    let back: Sparse = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.values[..], [0, 7, 0, 0, 0]);
+}
+
+#[test]
+fn sparse_rejects_out_of_bounds_items() {
+    let json = r#"{"values":{"len":2,"fill":0,"items":{"2":7}}}"#;
+    let err = serde_json::from_str::<Sparse>(json).unwrap_err();
+    assert!(err.to_string().contains("out of bounds"));
+}
+
+#[test]
+fn sparse_rejects_unallocatable_length() {
+    let json = r#"{"values":{"len":18446744073709551615,"fill":0,"items":{}}}"#;
+    let err = serde_json::from_str::<Sparse>(json).unwrap_err();
+    assert!(err.to_string().contains("cannot allocate"));
+}
+
+#[test]
+fn sparse_keeps_the_fill_value() {
+    let mut values = ExpandVec::with_fill(u32::MAX);
+    *values.expand_get_mut(3) = 5;
+    let json = serde_json::to_string(&SparseSentinels { values }).unwrap();
+    assert_eq!(
+        json,
+        r#"{"values":{"len":4,"fill":4294967295,"items":{"3":5}}}"#
+    );
+    let mut back: SparseSentinels = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.values[..], [u32::MAX, u32::MAX, u32::MAX, 5]);
+    assert_eq!(*back.values.expand_get_mut(5), u32::MAX);
+}
+
+proptest! {
+    #[test]
+    fn sparse_round_trips(items in prop::collection::vec(prop_oneof![Just(0u32), any::<u32>()], 0..64)) {
+        let values = ExpandVec::from(items.clone());
+        let json = serde_json::to_string(&Sparse { values }).unwrap();
+        let back: Sparse = serde_json::from_str(&json).unwrap();
+        prop_assert_eq!(back.values.raw_vec(), items);
+    }
+}